@@ -0,0 +1,518 @@
+// Leader-key chords for prompt libraries too big for a shortcut per prompt.
+//
+// Pressing the configured leader (e.g. `alt+shift+p`) starts a chord. For the
+// next `chordTimeoutMs` the letters that can continue a prompt's mnemonic are
+// registered as bare global shortcuts, so typing `r` then `f` injects the
+// prompt whose mnemonic is "rf". Those letters are only registered while a
+// chord is pending; otherwise they would swallow normal typing everywhere.
+//
+// A mnemonic that is also the start of a longer one ("r" and "rf") waits for
+// the timeout before firing. `chord-pending` carries the remaining candidates
+// so the bar can show hints, and `chord-ended` says the chord is over.
+//
+// Shortcut handlers run while the plugin holds its registry lock, so every
+// registration change here happens on a separate thread.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::prompts::{self, PromptLibrary};
+use crate::recorder;
+use crate::shortcuts::{self, Binding, ShortcutStatus};
+
+pub const CHORD_PENDING: &str = "chord-pending";
+pub const CHORD_ENDED: &str = "chord-ended";
+
+pub const MAX_MNEMONIC_LEN: usize = 2;
+const CANCEL_KEY: &str = "Escape";
+// Shortest wait for the next letter, whatever the settings say.
+const MIN_TIMEOUT_MS: u64 = 300;
+
+// The registered leader shortcut and the string it was configured as.
+static LEADER: Lazy<Mutex<Option<(Shortcut, String)>>> = Lazy::new(|| Mutex::new(None));
+static LEADER_STATUS: Lazy<Mutex<Option<ShortcutStatus>>> = Lazy::new(|| Mutex::new(None));
+
+struct PendingChord {
+    generation: u64,
+    typed: String,
+    // Second-stage keys currently registered for this chord.
+    keys: Vec<Shortcut>,
+}
+
+static PENDING: Lazy<Mutex<Option<PendingChord>>> = Lazy::new(|| Mutex::new(None));
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChordHint {
+    pub mnemonic: String,
+    pub prompt_id: String,
+    pub title: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChordPending {
+    // Letters typed after the leader so far.
+    pub typed: String,
+    pub hints: Vec<ChordHint>,
+    pub timeout_ms: u64,
+}
+
+// Normalise and check a mnemonic: one or two ASCII letters, lowercased.
+pub fn normalize_mnemonic(mnemonic: &str) -> Result<String, String> {
+    let mnemonic = mnemonic.trim().to_lowercase();
+    let len = mnemonic.chars().count();
+    if len == 0 || len > MAX_MNEMONIC_LEN || !mnemonic.chars().all(|c| c.is_ascii_lowercase()) {
+        return Err(format!("Mnemonic '{}' must be one or two letters", mnemonic));
+    }
+    Ok(mnemonic)
+}
+
+pub fn leader() -> Option<Shortcut> {
+    LEADER.lock().unwrap().as_ref().map(|(shortcut, _)| *shortcut)
+}
+
+pub fn leader_status() -> Option<ShortcutStatus> {
+    LEADER_STATUS.lock().unwrap().clone()
+}
+
+fn register_leader_handler(app: &AppHandle, shortcut: Shortcut, label: String) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed && !recorder::intercept(&label) {
+                println!("🎹 Chord leader ({}) pressed", label);
+                let app = app.clone();
+                std::thread::spawn(move || begin(&app));
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+// Switch the leader to `shortcut`, or disable chords when it is empty. Returns
+// `None` when chords are disabled. If the new leader can't be registered the
+// previous one stays.
+pub fn replace_leader(app: &AppHandle, shortcut: &str) -> Option<ShortcutStatus> {
+    let shortcut = shortcut.trim();
+    let previous = LEADER.lock().unwrap().clone();
+    // With no leader live, a rejected one is still the configured one.
+    let rejected = |status: ShortcutStatus| {
+        if previous.is_none() {
+            *LEADER_STATUS.lock().unwrap() = Some(status.clone());
+        }
+        Some(status)
+    };
+
+    let parsed = if shortcut.is_empty() {
+        None
+    } else {
+        match shortcuts::parse(shortcut) {
+            Ok(parsed) => Some(parsed),
+            Err(e) => return rejected(ShortcutStatus::failed(shortcut, Binding::Leader, e)),
+        }
+    };
+
+    if previous.as_ref().map(|(old, _)| *old) == parsed {
+        match parsed {
+            Some(parsed) => *LEADER.lock().unwrap() = Some((parsed, shortcut.to_string())),
+            None => *LEADER_STATUS.lock().unwrap() = None,
+        }
+        return leader_status();
+    }
+    if let Some(parsed) = parsed {
+        if let Some(owner) = shortcuts::owner_of(parsed, &Binding::Leader) {
+            println!("⚠️ Chord leader {} conflicts with {}", shortcut, owner);
+            return rejected(ShortcutStatus::conflict(shortcut, Binding::Leader, &owner));
+        }
+    }
+
+    if let Some((old, old_label)) = &previous {
+        println!("🔄 Releasing chord leader {}", old_label);
+        if let Err(e) = app.global_shortcut().unregister(*old) {
+            let error = format!("Failed to unregister shortcut '{}': {}", old_label, e);
+            return Some(ShortcutStatus::failed(shortcut, Binding::Leader, error));
+        }
+        *LEADER.lock().unwrap() = None;
+    }
+
+    let Some(parsed) = parsed else {
+        println!("🎹 Chords disabled");
+        *LEADER_STATUS.lock().unwrap() = None;
+        return None;
+    };
+
+    match register_leader_handler(app, parsed, shortcut.to_string()) {
+        Ok(()) => {
+            println!("✅ Chord leader is now {}", shortcut);
+            *LEADER.lock().unwrap() = Some((parsed, shortcut.to_string()));
+            let status = ShortcutStatus::registered(shortcut, Binding::Leader);
+            *LEADER_STATUS.lock().unwrap() = Some(status.clone());
+            Some(status)
+        }
+        Err(e) => {
+            println!("❌ Failed to register chord leader {}: {}", shortcut, e);
+            let status = ShortcutStatus::failed(shortcut, Binding::Leader, format!("Failed to register shortcut '{}': {}", shortcut, e));
+            match previous {
+                Some((old, old_label)) => match register_leader_handler(app, old, old_label.clone()) {
+                    Ok(()) => {
+                        println!("↩️  Restored previous chord leader {}", old_label);
+                        *LEADER.lock().unwrap() = Some((old, old_label));
+                    }
+                    Err(e) => {
+                        println!("❌ Failed to restore previous chord leader {}: {}", old_label, e);
+                        *LEADER_STATUS.lock().unwrap() = Some(ShortcutStatus::failed(&old_label, Binding::Leader, e));
+                    }
+                },
+                None => *LEADER_STATUS.lock().unwrap() = Some(status.clone()),
+            }
+            Some(status)
+        }
+    }
+}
+
+// Give the leader's keys up to `owner`, which outranks it. The leader stays
+// configured but is reported as a conflict until it is changed.
+pub fn yield_leader(app: &AppHandle, owner: &str) {
+    let Some((shortcut, label)) = LEADER.lock().unwrap().take() else {
+        return;
+    };
+    println!("🔓 Chord leader {} gives way to {}", label, owner);
+    if let Err(e) = app.global_shortcut().unregister(shortcut) {
+        println!("⚠️ Failed to unregister {}: {}", label, e);
+    }
+    *LEADER_STATUS.lock().unwrap() = Some(ShortcutStatus::conflict(&label, Binding::Leader, owner));
+}
+
+// Prompts whose mnemonic starts with `typed`, in library order.
+fn candidates(library: &PromptLibrary, typed: &str) -> Vec<ChordHint> {
+    library
+        .prompts
+        .iter()
+        .filter_map(|p| {
+            let mnemonic = p.mnemonic.as_deref()?;
+            mnemonic.starts_with(typed).then(|| ChordHint {
+                mnemonic: mnemonic.to_string(),
+                prompt_id: p.id.clone(),
+                title: p.title.clone(),
+            })
+        })
+        .collect()
+}
+
+// The letters that can follow `typed` in some candidate, sorted and deduped.
+fn next_letters(hints: &[ChordHint], typed: &str) -> Vec<char> {
+    let mut letters: Vec<char> = hints
+        .iter()
+        .filter_map(|h| h.mnemonic[typed.len()..].chars().next())
+        .collect();
+    letters.sort();
+    letters.dedup();
+    letters
+}
+
+// How long to wait for the next letter given the configured timeout.
+fn chord_timeout_ms(configured: u64) -> u64 {
+    configured.max(MIN_TIMEOUT_MS)
+}
+
+fn next_generation() -> u64 {
+    GENERATION.fetch_add(1, Ordering::SeqCst) + 1
+}
+
+fn begin(app: &AppHandle) {
+    // A second leader press restarts the chord.
+    end(app, "restarted");
+    let generation = next_generation();
+    *PENDING.lock().unwrap() = Some(PendingChord {
+        generation,
+        typed: String::new(),
+        keys: Vec::new(),
+    });
+    step(app, String::new(), generation);
+}
+
+// Move the chord to `typed`: fire if it names exactly one prompt, give up if
+// it names none, otherwise wait for the next letter. `generation` is the
+// chord state this step owns; if a timeout or another key press has moved the
+// chord on in the meantime, the step changes nothing.
+fn step(app: &AppHandle, typed: String, generation: u64) {
+    let library = match prompts::list(app) {
+        Ok(library) => library,
+        Err(e) => {
+            println!("❌ Failed to load prompts for chord: {}", e);
+            end_if_current(app, generation, "error");
+            return;
+        }
+    };
+    let mut hints = candidates(&library, &typed);
+
+    let exact = hints.iter().find(|h| h.mnemonic == typed).cloned();
+    let longer = hints.iter().any(|h| h.mnemonic.len() > typed.len());
+    if hints.is_empty() {
+        println!("🎹 No prompt for chord '{}'", typed);
+        end_if_current(app, generation, "no match");
+        return;
+    }
+    if let (Some(hint), false) = (&exact, longer) {
+        if end_if_current(app, generation, "matched") {
+            fire(app, hint);
+        }
+        return;
+    }
+
+    // Take this step's keys back before registering the next ones, which may
+    // be the same keys.
+    let old_keys = match PENDING.lock().unwrap().as_mut() {
+        Some(pending) if pending.generation == generation => std::mem::take(&mut pending.keys),
+        _ => return,
+    };
+    unregister_keys(app, old_keys);
+
+    // Register the letters that can come next and wait for one. A bare key
+    // that another binding (say a prompt bound to "r") already owns is
+    // skipped, not taken over, and so are the prompts it would lead to.
+    let mut keys = Vec::new();
+    let mut skipped = Vec::new();
+    for letter in next_letters(&hints, &typed).iter().map(|c| c.to_string()).chain([CANCEL_KEY.to_string()]) {
+        if let Some(owner) = shortcuts::parse(&letter).ok().and_then(|key| shortcuts::owner_of(key, &Binding::Leader)) {
+            println!("⚠️ Chord key {} is already used by {}, skipping it", letter, owner);
+            skipped.push(letter);
+            continue;
+        }
+        match register_key(app, &letter, generation) {
+            Ok(shortcut) => keys.push(shortcut),
+            Err(e) => {
+                println!("⚠️ Failed to register chord key {}: {}", letter, e);
+                skipped.push(letter);
+            }
+        }
+    }
+    hints.retain(|h| h.mnemonic == typed || !skipped.iter().any(|letter| h.mnemonic[typed.len()..].starts_with(letter.as_str())));
+    if hints.is_empty() {
+        println!("🎹 Every key that could continue chord '{}' is taken", typed);
+        unregister_keys(app, keys);
+        end_if_current(app, generation, "no match");
+        return;
+    }
+
+    // Hand the keys to the chord unless it moved on while they were being
+    // registered.
+    let stale = {
+        let mut pending = PENDING.lock().unwrap();
+        match pending.as_mut() {
+            Some(pending) if pending.generation == generation => {
+                pending.typed = typed.clone();
+                pending.keys = keys;
+                None
+            }
+            _ => Some(keys),
+        }
+    };
+    if let Some(keys) = stale {
+        unregister_keys(app, keys);
+        return;
+    }
+
+    let timeout_ms = chord_timeout_ms(crate::load_settings(app).chord_timeout_ms);
+    println!("🎹 Chord pending '{}' ({} candidates)", typed, hints.len());
+    if let Err(e) = app.emit(CHORD_PENDING, ChordPending { typed, hints, timeout_ms }) {
+        println!("❌ Failed to emit {} event: {}", CHORD_PENDING, e);
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(timeout_ms));
+        expire(&app, generation, exact);
+    });
+}
+
+fn register_key(app: &AppHandle, key: &str, generation: u64) -> Result<Shortcut, String> {
+    let shortcut = shortcuts::parse(key)?;
+    let key = key.to_string();
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                let app = app.clone();
+                let key = key.clone();
+                std::thread::spawn(move || press(&app, &key, generation));
+            }
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(shortcut)
+}
+
+fn press(app: &AppHandle, key: &str, generation: u64) {
+    // Check and claim the chord under one lock: from here on the timeout and
+    // any other key press of this generation are stale.
+    let (typed, claimed) = {
+        let mut pending = PENDING.lock().unwrap();
+        match pending.as_mut() {
+            Some(pending) if pending.generation == generation => {
+                pending.generation = next_generation();
+                (pending.typed.clone(), pending.generation)
+            }
+            // A stale key press from a chord that already moved on.
+            _ => return,
+        }
+    };
+
+    if key == CANCEL_KEY {
+        end_if_current(app, claimed, "cancelled");
+        return;
+    }
+    step(app, format!("{}{}", typed, key.to_lowercase()), claimed);
+}
+
+// The timeout ran out: fire the exact match if there was one, else give up.
+fn expire(app: &AppHandle, generation: u64, exact: Option<ChordHint>) {
+    if end_if_current(app, generation, "timed out") {
+        if let Some(hint) = exact {
+            fire(app, &hint);
+        }
+    }
+}
+
+fn fire(app: &AppHandle, hint: &ChordHint) {
+    println!("🚀 Chord '{}' → {}", hint.mnemonic, hint.title);
+    shortcuts::emit_inject(app, &hint.prompt_id);
+}
+
+fn unregister_keys(app: &AppHandle, keys: Vec<Shortcut>) {
+    if !keys.is_empty() {
+        if let Err(e) = app.global_shortcut().unregister_multiple(keys) {
+            println!("⚠️ Failed to release chord keys: {}", e);
+        }
+    }
+}
+
+// End the chord whatever state it is in.
+fn end(app: &AppHandle, reason: &str) {
+    let pending = PENDING.lock().unwrap().take();
+    finish(app, pending, reason);
+}
+
+// End the chord if it is still at `generation`. Returns whether it was, i.e.
+// whether the caller still speaks for the chord.
+fn end_if_current(app: &AppHandle, generation: u64, reason: &str) -> bool {
+    let pending = {
+        let mut pending = PENDING.lock().unwrap();
+        if pending.as_ref().map(|p| p.generation) != Some(generation) {
+            return false;
+        }
+        pending.take()
+    };
+    finish(app, pending, reason);
+    true
+}
+
+fn finish(app: &AppHandle, pending: Option<PendingChord>, reason: &str) {
+    let Some(pending) = pending else { return };
+    unregister_keys(app, pending.keys);
+    println!("🎹 Chord ended ({})", reason);
+    if let Err(e) = app.emit(CHORD_ENDED, reason) {
+        println!("❌ Failed to emit {} event: {}", CHORD_ENDED, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompts::Prompt;
+
+    fn prompt(id: &str, mnemonic: Option<&str>) -> Prompt {
+        Prompt {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: String::new(),
+            color: String::new(),
+            folder_id: None,
+            key_macro: None,
+            shortcut: None,
+            mnemonic: mnemonic.map(str::to_string),
+        }
+    }
+
+    fn library(prompts: Vec<Prompt>) -> PromptLibrary {
+        PromptLibrary { prompts, folders: Vec::new() }
+    }
+
+    fn mnemonics(hints: &[ChordHint]) -> Vec<&str> {
+        hints.iter().map(|h| h.mnemonic.as_str()).collect()
+    }
+
+    #[test]
+    fn mnemonics_are_one_or_two_letters() {
+        assert_eq!(normalize_mnemonic("r"), Ok("r".to_string()));
+        assert_eq!(normalize_mnemonic(" rf "), Ok("rf".to_string()));
+        assert_eq!(normalize_mnemonic(""), Err("Mnemonic '' must be one or two letters".to_string()));
+        assert_eq!(normalize_mnemonic("rfx"), Err("Mnemonic 'rfx' must be one or two letters".to_string()));
+    }
+
+    #[test]
+    fn mnemonics_are_lowercased() {
+        assert_eq!(normalize_mnemonic("RF"), Ok("rf".to_string()));
+        assert_eq!(normalize_mnemonic("Rf"), Ok("rf".to_string()));
+    }
+
+    #[test]
+    fn non_letter_mnemonics_are_rejected() {
+        assert!(normalize_mnemonic("r1").is_err());
+        assert!(normalize_mnemonic("r f").is_err());
+        // Two characters but not ASCII letters.
+        assert_eq!(normalize_mnemonic("éa"), Err("Mnemonic 'éa' must be one or two letters".to_string()));
+        assert!(normalize_mnemonic("ß").is_err());
+    }
+
+    #[test]
+    fn candidates_start_with_what_was_typed() {
+        let library = library(vec![
+            prompt("refactor", Some("rf")),
+            prompt("no-mnemonic", None),
+            prompt("tests", Some("t")),
+            prompt("rename", Some("rn")),
+        ]);
+        assert_eq!(mnemonics(&candidates(&library, "")), ["rf", "t", "rn"]);
+        assert_eq!(mnemonics(&candidates(&library, "r")), ["rf", "rn"]);
+        assert_eq!(mnemonics(&candidates(&library, "rn")), ["rn"]);
+        assert!(candidates(&library, "x").is_empty());
+
+        let hint = &candidates(&library, "t")[0];
+        assert_eq!((hint.prompt_id.as_str(), hint.title.as_str()), ("tests", "tests"));
+    }
+
+    #[test]
+    fn a_mnemonic_that_prefixes_another_keeps_both_candidates() {
+        let library = library(vec![prompt("review", Some("r")), prompt("refactor", Some("rf"))]);
+        let hints = candidates(&library, "r");
+        assert_eq!(mnemonics(&hints), ["r", "rf"]);
+        // "r" is an exact match, but "f" can still follow it.
+        assert_eq!(next_letters(&hints, "r"), ['f']);
+        assert_eq!(mnemonics(&candidates(&library, "rf")), ["rf"]);
+    }
+
+    #[test]
+    fn next_letters_are_sorted_and_deduplicated() {
+        let library = library(vec![
+            prompt("a", Some("rn")),
+            prompt("b", Some("tf")),
+            prompt("c", Some("rf")),
+            prompt("d", Some("t")),
+        ]);
+        assert_eq!(next_letters(&candidates(&library, ""), ""), ['r', 't']);
+        assert_eq!(next_letters(&candidates(&library, "r"), "r"), ['f', 'n']);
+        assert_eq!(next_letters(&candidates(&library, "t"), "t"), ['f']);
+    }
+
+    #[test]
+    fn short_timeouts_are_raised_to_the_minimum() {
+        assert_eq!(chord_timeout_ms(0), MIN_TIMEOUT_MS);
+        assert_eq!(chord_timeout_ms(MIN_TIMEOUT_MS - 1), MIN_TIMEOUT_MS);
+        assert_eq!(chord_timeout_ms(MIN_TIMEOUT_MS), MIN_TIMEOUT_MS);
+        assert_eq!(chord_timeout_ms(1500), 1500);
+    }
+}
@@ -0,0 +1,223 @@
+// Typed change notifications pushed from the backend to every webview.
+//
+// Any mutation done through a backend command emits one of these events with a
+// diff of what changed, so windows no longer need to poll the store files.
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+use crate::prompts::{Folder, Prompt, PromptLibrary};
+
+pub const PROMPTS_CHANGED: &str = "prompts-changed";
+pub const SETTINGS_CHANGED: &str = "settings-changed";
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptsChanged {
+    pub added: Vec<Prompt>,
+    pub updated: Vec<Prompt>,
+    pub removed: Vec<String>,
+    // Prompt ids in their new order, so reorders are visible even when no
+    // prompt was added, updated or removed.
+    pub order: Vec<String>,
+    // Folders are few and their membership lists change with almost every
+    // prompt edit, so the full list is sent rather than a diff.
+    pub folders: Vec<Folder>,
+}
+
+impl PromptsChanged {
+    pub fn between(before: &PromptLibrary, after: &PromptLibrary) -> Self {
+        let added = after
+            .prompts
+            .iter()
+            .filter(|p| !before.prompts.iter().any(|b| b.id == p.id))
+            .cloned()
+            .collect();
+        let updated = after
+            .prompts
+            .iter()
+            .filter(|p| before.prompts.iter().any(|b| b.id == p.id && b != *p))
+            .cloned()
+            .collect();
+        let removed = before
+            .prompts
+            .iter()
+            .filter(|b| !after.prompts.iter().any(|p| p.id == b.id))
+            .map(|b| b.id.clone())
+            .collect();
+
+        Self {
+            added,
+            updated,
+            removed,
+            order: after.prompts.iter().map(|p| p.id.clone()).collect(),
+            folders: after.folders.clone(),
+        }
+    }
+
+    pub fn is_empty(&self, before: &PromptLibrary) -> bool {
+        self.added.is_empty()
+            && self.updated.is_empty()
+            && self.removed.is_empty()
+            && self.order.iter().eq(before.prompts.iter().map(|p| &p.id))
+            && self.folders == before.folders
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsChanged {
+    // Names of the settings fields whose value changed.
+    pub changed: Vec<String>,
+    pub settings: Value,
+}
+
+impl SettingsChanged {
+    pub fn between(before: &Value, after: &Value) -> Self {
+        let mut changed: Vec<String> = match (before.as_object(), after.as_object()) {
+            (Some(before), Some(after)) => after
+                .iter()
+                .filter(|(key, value)| before.get(key.as_str()) != Some(value))
+                .map(|(key, _)| key.clone())
+                .chain(before.keys().filter(|key| !after.contains_key(key.as_str())).cloned())
+                .collect(),
+            _ => Vec::new(),
+        };
+        changed.sort();
+
+        Self {
+            changed,
+            settings: after.clone(),
+        }
+    }
+}
+
+pub fn emit_prompts_changed(app: &AppHandle, before: &PromptLibrary, after: &PromptLibrary) {
+    let diff = PromptsChanged::between(before, after);
+    if diff.is_empty(before) {
+        return;
+    }
+
+    println!(
+        "📡 Emitting {} (+{} ~{} -{})",
+        PROMPTS_CHANGED,
+        diff.added.len(),
+        diff.updated.len(),
+        diff.removed.len()
+    );
+    if let Err(e) = app.emit(PROMPTS_CHANGED, diff) {
+        println!("❌ Failed to emit {} event: {}", PROMPTS_CHANGED, e);
+    }
+}
+
+pub fn emit_settings_changed(app: &AppHandle, before: &Value, after: &Value) {
+    let diff = SettingsChanged::between(before, after);
+    if diff.changed.is_empty() {
+        return;
+    }
+
+    println!("📡 Emitting {} ({:?})", SETTINGS_CHANGED, diff.changed);
+    if let Err(e) = app.emit(SETTINGS_CHANGED, diff) {
+        println!("❌ Failed to emit {} event: {}", SETTINGS_CHANGED, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn prompt(id: &str, content: &str) -> Prompt {
+        Prompt {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: content.to_string(),
+            color: String::new(),
+            folder_id: None,
+            key_macro: None,
+            shortcut: None,
+            mnemonic: None,
+        }
+    }
+
+    fn folder(id: &str, name: &str) -> Folder {
+        Folder {
+            id: id.to_string(),
+            name: name.to_string(),
+            color: String::new(),
+            is_expanded: false,
+            prompts: Vec::new(),
+        }
+    }
+
+    fn library(prompts: Vec<Prompt>, folders: Vec<Folder>) -> PromptLibrary {
+        PromptLibrary { prompts, folders }
+    }
+
+    fn ids(prompts: &[Prompt]) -> Vec<&str> {
+        prompts.iter().map(|p| p.id.as_str()).collect()
+    }
+
+    #[test]
+    fn prompt_diff_lists_added_updated_and_removed() {
+        let before = library(vec![prompt("a", "one"), prompt("b", "two"), prompt("c", "three")], Vec::new());
+        let after = library(vec![prompt("a", "one"), prompt("b", "TWO"), prompt("d", "four")], Vec::new());
+
+        let diff = PromptsChanged::between(&before, &after);
+        assert_eq!(ids(&diff.added), ["d"]);
+        assert_eq!(ids(&diff.updated), ["b"]);
+        assert_eq!(diff.updated[0].content, "TWO");
+        assert_eq!(diff.removed, ["c"]);
+        assert_eq!(diff.order, ["a", "b", "d"]);
+        assert!(!diff.is_empty(&before));
+    }
+
+    #[test]
+    fn unchanged_library_gives_an_empty_diff() {
+        let before = library(vec![prompt("a", "one"), prompt("b", "two")], vec![folder("f", "Work")]);
+        let diff = PromptsChanged::between(&before, &before.clone());
+        assert!(diff.added.is_empty() && diff.updated.is_empty() && diff.removed.is_empty());
+        assert!(diff.is_empty(&before));
+    }
+
+    #[test]
+    fn reorder_alone_is_a_change() {
+        let before = library(vec![prompt("a", "one"), prompt("b", "two")], Vec::new());
+        let after = library(vec![prompt("b", "two"), prompt("a", "one")], Vec::new());
+
+        let diff = PromptsChanged::between(&before, &after);
+        assert!(diff.added.is_empty() && diff.updated.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.order, ["b", "a"]);
+        assert!(!diff.is_empty(&before));
+    }
+
+    #[test]
+    fn folder_only_change_is_a_change() {
+        let before = library(vec![prompt("a", "one")], vec![folder("f", "Work")]);
+        let after = library(vec![prompt("a", "one")], vec![folder("f", "Office")]);
+
+        let diff = PromptsChanged::between(&before, &after);
+        assert!(diff.added.is_empty() && diff.updated.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.folders, after.folders);
+        assert!(!diff.is_empty(&before));
+    }
+
+    #[test]
+    fn settings_diff_names_changed_added_and_removed_keys() {
+        let before = json!({ "toggleShortcut": "alt+space", "pasteThreshold": 200, "targetMode": "last" });
+        let after = json!({ "toggleShortcut": "alt+space", "pasteThreshold": 500, "chordLeader": "alt+p" });
+
+        let diff = SettingsChanged::between(&before, &after);
+        assert_eq!(diff.changed, ["chordLeader", "pasteThreshold", "targetMode"]);
+        assert_eq!(diff.settings, after);
+    }
+
+    #[test]
+    fn unchanged_settings_give_no_changed_keys() {
+        let settings = json!({ "toggleShortcut": "alt+space", "ignoreList": ["Terminal"] });
+        assert!(SettingsChanged::between(&settings, &settings.clone()).changed.is_empty());
+        // Not objects: nothing to compare key by key.
+        assert!(SettingsChanged::between(&json!(null), &settings).changed.is_empty());
+    }
+}
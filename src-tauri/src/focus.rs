@@ -0,0 +1,407 @@
+// Working out which app the bar was opened over, and putting focus back on
+// it afterwards. Everything goes through a `WindowSystem`, so the same logic
+// runs against the desktop and against the mock in tests.
+
+use std::time::{Duration, Instant};
+
+use crate::ignore_list;
+use crate::window_system::{WindowInfo, WindowSystem};
+use crate::wrappers;
+
+pub fn is_valid_app_name(name: &str) -> bool {
+    // Check for empty or very short names
+    if name.len() < 2 {
+        return false;
+    }
+    
+    // Check for our own app name (including variations)
+    if ignore_list::is_own_app(name) {
+        return false;
+    }
+    
+    // Additional validation rules can be added here
+    true
+}
+
+// Whether the ignore list in the settings rejects `app_name` as a target.
+pub fn is_problematic_app(app_name: &str) -> bool {
+    let verdict = ignore_list::check(&ignore_list::current(), app_name);
+    if !verdict.accepted {
+        println!("⚠️ {} is not a valid target: {}", app_name, verdict.reason);
+    }
+    !verdict.accepted
+}
+
+// The frontmost app, with a wrapper process like Electron resolved to the app
+// it runs. Our own windows and unresolvable wrappers give `None`.
+pub fn frontmost_app(ws: &dyn WindowSystem) -> Option<WindowInfo> {
+    let mut window = ws.frontmost_window()?;
+    println!("🔍 Frontmost process name: {}", window.app_name);
+
+    // Wrappers like Electron need resolving to the app they actually run
+    if let Some(wrapper) = wrappers::find(&window.app_name) {
+        println!("🔍 Detected wrapper process {}, attempting to resolve the app it runs...", window.app_name);
+        match ws.resolve_wrapper(&window) {
+            Some(app_name) => {
+                window.app_name = app_name;
+                return Some(window);
+            }
+            None if wrapper.opaque => {
+                // Returning None avoids activating random Electron processes
+                println!("⚠️ Could not resolve {} process to the app it runs, returning None", window.app_name);
+                return None;
+            }
+            None => println!("ℹ️  Keeping unresolved wrapper name {}", window.app_name),
+        }
+    }
+
+    // For other apps, validate the name and return it
+    if !window.app_name.is_empty() && is_valid_app_name(&window.app_name) {
+        println!("🔍 Using process name: {}", window.app_name);
+        return Some(window);
+    }
+
+    println!("⚠️ Process name is empty or invalid");
+    None
+}
+
+// Bring back the exact window that was captured, so a prompt meant for one of
+// three Cursor windows doesn't land in whichever the OS picks. If that window
+// has closed, or only the app is known, the app itself is activated. Returns
+// what should now be frontmost: the window, or just its app after falling back.
+fn activate(ws: &dyn WindowSystem, window: &WindowInfo) -> Option<WindowInfo> {
+    if is_problematic_app(&window.app_name) {
+        println!("⚠️ Refusing to activate potentially problematic app: {}", window.app_name);
+        return None;
+    }
+    if window.is_specific() {
+        if ws.activate_window(window) {
+            return Some(window.clone());
+        }
+        println!("🔄 Window of {} not found, activating the app instead", window.app_name);
+    }
+    ws.activate_app(&window.app_name).then(|| WindowInfo::new(&window.app_name))
+}
+
+// Activation attempts before giving up on putting the target in front. The
+// first wait is doubled after every attempt, ~1.5s in all.
+const ACTIVATION_ATTEMPTS: u32 = 4;
+const FIRST_WAIT: Duration = Duration::from_millis(100);
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+// Time as `restore` sees it, so tests can run through every retry at once.
+pub trait Clock {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+// A clock that only moves when slept on, `slowdown` times as far as asked,
+// like a busy machine oversleeping.
+#[cfg(test)]
+pub struct FakeClock {
+    now: std::cell::Cell<Instant>,
+    started: Instant,
+    slowdown: u32,
+}
+
+#[cfg(test)]
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self::slowed_down(1)
+    }
+}
+
+#[cfg(test)]
+impl FakeClock {
+    pub fn slowed_down(slowdown: u32) -> Self {
+        let now = Instant::now();
+        Self { now: std::cell::Cell::new(now), started: now, slowdown }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.now.get() - self.started
+    }
+}
+
+#[cfg(test)]
+impl Clock for FakeClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration * self.slowdown);
+    }
+}
+
+fn is_frontmost(ws: &dyn WindowSystem, expected: &WindowInfo) -> bool {
+    match frontmost_app(ws) {
+        Some(front) if expected.is_specific() => front.same_window(expected),
+        Some(front) => front.app_name.eq_ignore_ascii_case(&expected.app_name),
+        None => false,
+    }
+}
+
+// Poll until `expected` is frontmost or `wait` is up. Asking the window
+// system can itself take a while (an `osascript` run on macOS), so the time
+// left is read from the clock rather than counted in polls.
+fn wait_until_frontmost(ws: &dyn WindowSystem, expected: &WindowInfo, wait: Duration, clock: &dyn Clock) -> bool {
+    let deadline = clock.now() + wait;
+    loop {
+        if is_frontmost(ws, expected) {
+            return true;
+        }
+        let now = clock.now();
+        if now >= deadline {
+            return false;
+        }
+        clock.sleep(POLL_INTERVAL.min(deadline - now));
+    }
+}
+
+// What has focus, for error messages.
+fn describe_frontmost(ws: &dyn WindowSystem) -> String {
+    match ws.frontmost_window() {
+        Some(window) if ignore_list::is_own_app(&window.app_name) => "Prompt Buddy itself".to_string(),
+        Some(WindowInfo { app_name, title: Some(title), .. }) => format!("{} (\"{}\")", app_name, title),
+        Some(window) => window.app_name,
+        None => "no window".to_string(),
+    }
+}
+
+// Give focus back to the first of `recent` (most recent first) that may be a
+// target, and make sure it has it before anything is typed. Ignored apps are
+// walked past so focus never lands on the Dock or an unresolved Electron
+// process; falling back past the first one left would type into an app nobody
+// picked.
+//
+// An activation call succeeding only means the request was made, so the
+// frontmost window is polled, and the activation retried with a growing wait.
+// If the target never comes up the error says what has focus instead: typing
+// there (into Prompt Buddy, or a chat someone else opened) is worse than not
+// typing at all.
+pub fn restore(ws: &dyn WindowSystem, recent: &[WindowInfo], clock: &dyn Clock) -> Result<(), String> {
+    let Some(target) = recent.iter().find(|window| !is_problematic_app(&window.app_name)) else {
+        return Err(format!("No app to type into: focus is on {}", describe_frontmost(ws)));
+    };
+
+    let mut wait = FIRST_WAIT;
+    for attempt in 1..=ACTIVATION_ATTEMPTS {
+        println!("🔄 Activating {} (attempt {}/{})", target.app_name, attempt, ACTIVATION_ATTEMPTS);
+        match activate(ws, target) {
+            Some(expected) if wait_until_frontmost(ws, &expected, wait, clock) => {
+                println!("✅ {} is frontmost", expected.app_name);
+                return Ok(());
+            }
+            Some(_) => println!("⏳ {} hasn't come to the front after {:?}", target.app_name, wait),
+            None => {
+                println!("⚠️ Failed to activate {}, retrying in {:?}", target.app_name, wait);
+                clock.sleep(wait);
+            }
+        }
+        wait *= 2;
+    }
+
+    Err(format!(
+        "Couldn't bring {} to the front, focus is on {}. Nothing was typed.",
+        target.app_name,
+        describe_frontmost(ws)
+    ))
+}
+
+// Check that the user's click landed in a window we may type into. Any app
+// will do, the click is how they pick it; only our own window or none at all
+// (the desktop) is refused.
+pub fn check_frontmost(ws: &dyn WindowSystem) -> Result<WindowInfo, String> {
+    match ws.frontmost_window() {
+        Some(window) if !ignore_list::is_own_app(&window.app_name) => Ok(window),
+        _ => Err(format!("Focus is on {}, not an app to type into. Nothing was typed.", describe_frontmost(ws))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::window_system::mock::MockWindowSystem;
+
+    fn windows(apps: &[&str]) -> Vec<WindowInfo> {
+        apps.iter().map(|app| WindowInfo::new(app)).collect()
+    }
+
+    #[test]
+    fn restore_activates_last_app() {
+        let ws = MockWindowSystem::with_apps(&["Prompt Buddy", "Cursor", "Safari"]);
+        restore(&ws, &windows(&["Safari", "Cursor"]), &FakeClock::default()).unwrap();
+        assert_eq!(ws.activations(), vec!["Safari"]);
+        assert_eq!(ws.frontmost_window().unwrap().app_name, "Safari");
+    }
+
+    #[test]
+    fn restore_falls_back_to_previous_app_when_last_is_problematic() {
+        let ws = MockWindowSystem::with_apps(&["Prompt Buddy", "Cursor"]);
+        restore(&ws, &windows(&["Electron", "Cursor"]), &FakeClock::default()).unwrap();
+        assert_eq!(ws.activations(), vec!["Cursor"]);
+    }
+
+    #[test]
+    fn restore_walks_back_past_every_problematic_app() {
+        let ws = MockWindowSystem::with_apps(&["Prompt Buddy", "Dock", "Cursor", "Safari"]);
+        restore(&ws, &windows(&["Dock", "Electron", "Prompt Buddy", "Cursor", "Safari"]), &FakeClock::default()).unwrap();
+        assert_eq!(ws.activations(), vec!["Cursor"]);
+    }
+
+    #[test]
+    fn restore_activates_nothing_when_every_app_is_problematic() {
+        let ws = MockWindowSystem::with_apps(&["Prompt Buddy", "Dock"]);
+        assert_eq!(
+            restore(&ws, &windows(&["Electron", "Dock"]), &FakeClock::default()),
+            Err("No app to type into: focus is on Prompt Buddy itself".to_string())
+        );
+        assert!(restore(&ws, &windows(&["Prompt Buddy"]), &FakeClock::default()).is_err());
+        assert!(ws.activations().is_empty());
+    }
+
+    #[test]
+    fn restore_reports_failed_activation() {
+        let ws = MockWindowSystem::with_apps(&["Prompt Buddy", "Safari", "Cursor"]).refusing("Safari");
+        assert_eq!(
+            restore(&ws, &windows(&["Safari", "Cursor"]), &FakeClock::default()),
+            Err("Couldn't bring Safari to the front, focus is on Prompt Buddy itself. Nothing was typed.".to_string())
+        );
+        assert_eq!(ws.activations(), vec!["Safari"; ACTIVATION_ATTEMPTS as usize]);
+    }
+
+    fn cursor_window(id: u64, project: &str) -> WindowInfo {
+        WindowInfo {
+            title: Some(format!("{} — Cursor", project)),
+            pid: Some(42),
+            window_id: Some(id),
+            ..WindowInfo::new("Cursor")
+        }
+    }
+
+    #[test]
+    fn restore_brings_back_the_captured_window() {
+        let ws = MockWindowSystem::with_windows(vec![
+            WindowInfo::new("Prompt Buddy"),
+            cursor_window(1, "api"),
+            cursor_window(2, "web"),
+            cursor_window(3, "docs"),
+        ]);
+        restore(&ws, &[cursor_window(2, "web")], &FakeClock::default()).unwrap();
+        assert_eq!(ws.frontmost_window(), Some(cursor_window(2, "web")));
+        assert!(ws.activations().is_empty());
+    }
+
+    #[test]
+    fn restore_activates_the_app_when_the_window_has_closed() {
+        let ws = MockWindowSystem::with_windows(vec![
+            WindowInfo::new("Prompt Buddy"),
+            cursor_window(1, "api"),
+            cursor_window(2, "web"),
+        ]);
+        ws.close(&cursor_window(2, "web"));
+        restore(&ws, &[cursor_window(2, "web")], &FakeClock::default()).unwrap();
+        assert_eq!(ws.activations(), vec!["Cursor"]);
+        assert_eq!(ws.frontmost_window(), Some(cursor_window(1, "api")));
+    }
+
+    #[test]
+    fn restore_without_last_app_fails() {
+        let ws = MockWindowSystem::with_apps(&["Safari"]);
+        assert_eq!(restore(&ws, &[], &FakeClock::default()), Err("No app to type into: focus is on Safari".to_string()));
+        assert!(ws.activations().is_empty());
+    }
+
+    #[test]
+    fn restore_retries_activation_that_doesnt_take() {
+        let ws = MockWindowSystem::with_apps(&["Prompt Buddy", "Safari"]).ignoring("Safari", 2);
+        restore(&ws, &windows(&["Safari"]), &FakeClock::default()).unwrap();
+        assert_eq!(ws.activations(), vec!["Safari", "Safari", "Safari"]);
+        assert_eq!(ws.frontmost_window().unwrap().app_name, "Safari");
+    }
+
+    #[test]
+    fn restore_never_types_into_the_wrong_window() {
+        let ws = MockWindowSystem::with_apps(&["Prompt Buddy", "Safari"]).ignoring("Safari", usize::MAX);
+        assert_eq!(
+            restore(&ws, &windows(&["Safari"]), &FakeClock::default()),
+            Err("Couldn't bring Safari to the front, focus is on Prompt Buddy itself. Nothing was typed.".to_string())
+        );
+        assert_eq!(ws.activations().len(), ACTIVATION_ATTEMPTS as usize);
+    }
+
+    #[test]
+    fn check_frontmost_rejects_our_own_window() {
+        let ws = MockWindowSystem::with_apps(&["Prompt Buddy", "Safari"]);
+        assert!(check_frontmost(&ws).is_err());
+        ws.activate_app("Safari");
+        assert_eq!(check_frontmost(&ws).unwrap().app_name, "Safari");
+    }
+
+    #[test]
+    fn check_frontmost_accepts_whatever_app_was_clicked() {
+        let ws = MockWindowSystem::with_windows(vec![WindowInfo::new("Electron"), cursor_window(1, "api")]);
+        assert_eq!(check_frontmost(&ws), Ok(WindowInfo::new("Electron")));
+        ws.activate_window(&cursor_window(1, "api"));
+        assert_eq!(check_frontmost(&ws), Ok(cursor_window(1, "api")));
+
+        let desktop = MockWindowSystem::with_apps(&[]);
+        assert_eq!(
+            check_frontmost(&desktop),
+            Err("Focus is on no window, not an app to type into. Nothing was typed.".to_string())
+        );
+    }
+
+    #[test]
+    fn restore_gives_up_on_time_however_long_polls_take() {
+        let ws = MockWindowSystem::with_apps(&["Prompt Buddy", "Safari"]).ignoring("Safari", usize::MAX);
+        let total: Duration = (0..ACTIVATION_ATTEMPTS).map(|attempt| FIRST_WAIT * 2u32.pow(attempt)).sum();
+
+        let clock = FakeClock::default();
+        assert!(restore(&ws, &windows(&["Safari"]), &clock).is_err());
+        assert_eq!(clock.elapsed(), total);
+
+        // Every sleep overshooting threefold still only overshoots each wait
+        // by the last poll.
+        let clock = FakeClock::slowed_down(3);
+        assert!(restore(&ws, &windows(&["Safari"]), &clock).is_err());
+        assert!(clock.elapsed() <= total + POLL_INTERVAL * 3 * ACTIVATION_ATTEMPTS, "{:?}", clock.elapsed());
+    }
+
+    #[test]
+    fn restore_retries_as_soon_as_the_wait_is_up() {
+        let ws = MockWindowSystem::with_apps(&["Prompt Buddy", "Safari"]).ignoring("Safari", 1);
+        let clock = FakeClock::default();
+        restore(&ws, &windows(&["Safari"]), &clock).unwrap();
+        assert_eq!(ws.activations(), vec!["Safari", "Safari"]);
+        assert_eq!(clock.elapsed(), FIRST_WAIT);
+    }
+
+    #[test]
+    fn frontmost_app_resolves_electron_wrappers() {
+        let ws = MockWindowSystem::with_apps(&["Electron"]).wrapping("Electron", "Qoder");
+        assert_eq!(frontmost_app(&ws).unwrap().app_name, "Qoder");
+
+        let unresolved = MockWindowSystem::with_apps(&["Electron"]);
+        assert_eq!(frontmost_app(&unresolved), None);
+    }
+
+    #[test]
+    fn frontmost_app_skips_our_own_window() {
+        let ws = MockWindowSystem::with_apps(&["Prompt Buddy", "Safari"]);
+        assert_eq!(frontmost_app(&ws), None);
+    }
+}
@@ -0,0 +1,210 @@
+// The windows prompts were recently meant for, most recent first.
+//
+// Every time the bar opens, the window it was opened over is pushed onto a
+// bounded stack together with what the routing rules decided for it. The top
+// entry is where prompts go; the bar can offer any other entry as an "inject
+// into…" target, which moves it back to the top, and restoring focus walks
+// down the stack past windows that are gone or ignored.
+//
+// Capturing the same window again (see `WindowInfo::same_window`) moves its
+// entry up instead of adding another one, while two windows of one app get an
+// entry each.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::Mutex;
+
+use crate::focus;
+use crate::routing::{self, AppContext, Route, RoutingRule};
+use crate::usage::now_ms;
+use crate::window_system::{WindowInfo, WindowSystem};
+
+// Enough to cover a working session's worth of windows.
+pub const MAX_ENTRIES: usize = 10;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusEntry {
+    // Unique for the session; kept when the window is captured again.
+    pub id: u64,
+    // `app_name` is the target app: wrappers resolved and routing applied.
+    #[serde(flatten)]
+    pub window: WindowInfo,
+    // Milliseconds since the Unix epoch of the latest capture.
+    pub captured_at: u64,
+    #[serde(skip)]
+    pub route: Route,
+}
+
+#[derive(Default)]
+pub struct FocusHistory {
+    entries: Vec<FocusEntry>,
+    next_id: u64,
+}
+
+impl FocusHistory {
+    pub fn push(&mut self, window: WindowInfo, route: Route, captured_at: u64) -> &FocusEntry {
+        let id = match self.entries.iter().position(|entry| entry.window.same_window(&window)) {
+            Some(index) => self.entries.remove(index).id,
+            None => {
+                self.next_id += 1;
+                self.next_id
+            }
+        };
+        self.entries.insert(0, FocusEntry { id, window, captured_at, route });
+        self.entries.truncate(MAX_ENTRIES);
+        &self.entries[0]
+    }
+
+    pub fn entries(&self) -> &[FocusEntry] {
+        &self.entries
+    }
+
+    pub fn latest(&self) -> Option<&FocusEntry> {
+        self.entries.first()
+    }
+
+    // Make entry `id` the current target.
+    pub fn promote(&mut self, id: u64) -> Option<&FocusEntry> {
+        let index = self.entries.iter().position(|entry| entry.id == id)?;
+        let entry = self.entries.remove(index);
+        self.entries.insert(0, entry);
+        self.entries.first()
+    }
+}
+
+// The frontmost window as a target, and what the routing rules decide for
+// it. `None` when our own bar or an unresolvable wrapper is in front, so
+// capturing then leaves the history alone.
+pub fn frontmost_target(ws: &dyn WindowSystem, rules: &[RoutingRule]) -> Option<(WindowInfo, Route)> {
+    let mut window = focus::frontmost_app(ws)?;
+    let context = AppContext {
+        app_name: window.app_name.clone(),
+        bundle_path: None,
+        window_title: window.title.clone(),
+    };
+    let route = routing::route(rules, &context);
+    // A rule sending prompts to another app makes this window irrelevant
+    if let Some(target_app) = route.target_app.as_ref().filter(|app| **app != window.app_name) {
+        window = WindowInfo::new(target_app);
+    }
+    Some((window, route))
+}
+
+static HISTORY: Lazy<Mutex<FocusHistory>> = Lazy::new(|| Mutex::new(FocusHistory::default()));
+
+// Record whatever is frontmost now as the current target. Called whenever
+// prompts may be about to go somewhere: the bar opening, and prompt shortcuts
+// and chords, which fire while the bar is hidden.
+pub fn capture(ws: &dyn WindowSystem) {
+    if let Some((window, route)) = frontmost_target(ws, &routing::rules()) {
+        record(window, route);
+    }
+}
+
+pub fn record(window: WindowInfo, route: Route) {
+    let mut history = HISTORY.lock().unwrap();
+    let entry = history.push(window, route, now_ms());
+    println!("💾 Remembering current frontmost app: {} (target #{})", entry.window.app_name, entry.id);
+}
+
+pub fn recent() -> Vec<FocusEntry> {
+    HISTORY.lock().unwrap().entries().to_vec()
+}
+
+pub fn latest() -> Option<FocusEntry> {
+    HISTORY.lock().unwrap().latest().cloned()
+}
+
+pub fn promote(id: u64) -> Option<FocusEntry> {
+    HISTORY.lock().unwrap().promote(id).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::window_system::mock::MockWindowSystem;
+
+    fn window(app: &str, pid: u32) -> WindowInfo {
+        WindowInfo {
+            pid: Some(pid),
+            ..WindowInfo::new(app)
+        }
+    }
+
+    #[test]
+    fn recapturing_a_window_moves_it_up_and_keeps_its_id() {
+        let mut history = FocusHistory::default();
+        let cursor = history.push(window("Cursor", 1), Route::default(), 1).id;
+        history.push(window("Safari", 2), Route::default(), 2);
+        assert_eq!(history.push(window("Cursor", 1), Route::default(), 3).id, cursor);
+
+        let apps: Vec<_> = history.entries().iter().map(|e| e.window.app_name.as_str()).collect();
+        assert_eq!(apps, vec!["Cursor", "Safari"]);
+        assert_eq!(history.latest().unwrap().captured_at, 3);
+    }
+
+    #[test]
+    fn windows_of_one_app_are_kept_apart() {
+        let mut history = FocusHistory::default();
+        for title in ["api — Cursor", "web — Cursor", "api — Cursor"] {
+            let window = WindowInfo {
+                title: Some(title.to_string()),
+                ..window("Cursor", 1)
+            };
+            history.push(window, Route::default(), 1);
+        }
+        let titles: Vec<_> = history.entries().iter().map(|e| e.window.title.as_deref().unwrap()).collect();
+        assert_eq!(titles, vec!["api — Cursor", "web — Cursor"]);
+    }
+
+    fn recent(history: &FocusHistory) -> Vec<WindowInfo> {
+        history.entries().iter().map(|e| e.window.clone()).collect()
+    }
+
+    #[test]
+    fn shortcut_pressed_over_a_different_app_targets_that_app() {
+        // The bar was last opened over Cursor; the user has since moved to
+        // Slack and presses a prompt shortcut there.
+        let ws = MockWindowSystem::with_apps(&["Slack", "Cursor", "Prompt Buddy"]);
+        let mut history = FocusHistory::default();
+        history.push(WindowInfo::new("Cursor"), Route::default(), 1);
+
+        let (window, route) = frontmost_target(&ws, &[]).unwrap();
+        history.push(window, route, 2);
+        focus::restore(&ws, &recent(&history), &focus::FakeClock::default()).unwrap();
+        assert_eq!(ws.frontmost_window().unwrap().app_name, "Slack");
+        assert_eq!(ws.activations(), vec!["Slack"]);
+    }
+
+    #[test]
+    fn shortcut_pressed_over_the_bar_keeps_the_last_target() {
+        let ws = MockWindowSystem::with_apps(&["Prompt Buddy", "Slack", "Cursor"]);
+        let mut history = FocusHistory::default();
+        history.push(WindowInfo::new("Cursor"), Route::default(), 1);
+
+        assert_eq!(frontmost_target(&ws, &[]), None);
+        focus::restore(&ws, &recent(&history), &focus::FakeClock::default()).unwrap();
+        assert_eq!(ws.frontmost_window().unwrap().app_name, "Cursor");
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let mut history = FocusHistory::default();
+        for pid in 0..(MAX_ENTRIES as u32 + 5) {
+            history.push(window("Terminal", pid), Route::default(), pid as u64);
+        }
+        assert_eq!(history.entries().len(), MAX_ENTRIES);
+        assert_eq!(history.latest().unwrap().window.pid, Some(MAX_ENTRIES as u32 + 4));
+    }
+
+    #[test]
+    fn promote_makes_an_entry_current() {
+        let mut history = FocusHistory::default();
+        let cursor = history.push(window("Cursor", 1), Route::default(), 1).id;
+        history.push(window("Safari", 2), Route::default(), 2);
+        assert_eq!(history.promote(cursor).unwrap().window.app_name, "Cursor");
+        assert_eq!(history.latest().unwrap().id, cursor);
+        assert!(history.promote(99).is_none());
+    }
+}
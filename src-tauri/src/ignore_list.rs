@@ -0,0 +1,154 @@
+// Which apps prompts may be sent to.
+//
+// Some processes must never become the target app: our own windows, wrappers
+// that couldn't be resolved, and system agents that are briefly frontmost
+// (macOS reports the Dock or `universalaccessd` now and then). Settings hold
+// deny patterns for those and allow patterns that override them, both
+// case-insensitive globs. Prompt Buddy itself is always rejected.
+//
+// `check` says which rule decided, so settings can explain a verdict.
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::routing::glob_matches;
+
+// The name of our own application (used to detect if we're trying to inject into ourselves)
+static APP_NAME: &str = "Prompt Buddy";
+// What the window system may call us instead: the binary name (X11 classes)
+// and the bundle identifier (Wayland app ids).
+static APP_IDS: [&str; 2] = ["prompt-buddy", "com.promptbuddy.app"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct IgnoreList {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+impl Default for IgnoreList {
+    fn default() -> Self {
+        Self {
+            allow: Vec::new(),
+            deny: default_deny(),
+        }
+    }
+}
+
+// Unresolved Electron, and the macOS daemons and agents the old hardcoded
+// checks rejected.
+fn default_deny() -> Vec<String> {
+    [
+        "Electron", "universalaccessd", "Dock", "Finder", "SystemUIServer", "ControlCenter",
+        "WindowServer", "loginwindow", "kernel_task", "launchd", "syslogd", "cfprefsd",
+        "distnoted", "NotificationCenter", "Spotlight", "mds", "mdworker", "*Agent",
+    ]
+    .iter()
+    .map(|pattern| pattern.to_string())
+    .collect()
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Verdict {
+    pub accepted: bool,
+    // Why, e.g. "denied by 'Dock'".
+    pub reason: String,
+}
+
+impl Verdict {
+    fn accept(reason: String) -> Self {
+        Self { accepted: true, reason }
+    }
+
+    fn reject(reason: String) -> Self {
+        Self { accepted: false, reason }
+    }
+}
+
+// The list currently in the settings, for the same reason as routing's rules.
+static LIST: Lazy<Mutex<IgnoreList>> = Lazy::new(|| Mutex::new(IgnoreList::default()));
+
+pub fn set(list: IgnoreList) {
+    *LIST.lock().unwrap() = list;
+}
+
+pub fn current() -> IgnoreList {
+    LIST.lock().unwrap().clone()
+}
+
+// Exact matches only: other apps may well have "Prompt" in their names.
+pub fn is_own_app(name: &str) -> bool {
+    name == APP_NAME || APP_IDS.contains(&name)
+}
+
+fn first_match<'a>(patterns: &'a [String], name: &str) -> Option<&'a str> {
+    patterns
+        .iter()
+        .map(|pattern| pattern.trim())
+        .find(|pattern| !pattern.is_empty() && glob_matches(pattern, name))
+}
+
+pub fn check(list: &IgnoreList, name: &str) -> Verdict {
+    if is_own_app(name) {
+        return Verdict::reject("is Prompt Buddy itself".to_string());
+    }
+    if let Some(pattern) = first_match(&list.allow, name) {
+        return Verdict::accept(format!("allowed by '{}'", pattern));
+    }
+    if let Some(pattern) = first_match(&list.deny, name) {
+        return Verdict::reject(format!("denied by '{}'", pattern));
+    }
+    Verdict::accept("matches no deny pattern".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_list_rejects_system_processes() {
+        let list = IgnoreList::default();
+        assert!(!check(&list, "Dock").accepted);
+        assert!(!check(&list, "Electron").accepted);
+        assert!(!check(&list, "CoreServicesUIAgent").accepted);
+    }
+
+    #[test]
+    fn default_list_accepts_apps_ending_in_d() {
+        let list = IgnoreList::default();
+        for name in ["Word", "Android", "Discord"] {
+            assert_eq!(check(&list, name), Verdict::accept("matches no deny pattern".to_string()));
+        }
+    }
+
+    #[test]
+    fn allow_overrides_deny() {
+        let list = IgnoreList {
+            allow: vec!["finder".to_string()],
+            ..IgnoreList::default()
+        };
+        assert_eq!(check(&list, "Finder"), Verdict::accept("allowed by 'finder'".to_string()));
+        assert_eq!(check(&list, "Dock"), Verdict::reject("denied by 'Dock'".to_string()));
+    }
+
+    #[test]
+    fn own_app_is_always_rejected() {
+        let list = IgnoreList {
+            allow: vec!["*".to_string()],
+            deny: Vec::new(),
+        };
+        assert!(!check(&list, "Prompt Buddy").accepted);
+        assert!(!check(&list, "prompt-buddy").accepted);
+        assert!(!check(&list, "com.promptbuddy.app").accepted);
+    }
+
+    #[test]
+    fn apps_with_similar_names_are_not_ours() {
+        let list = IgnoreList::default();
+        for name in ["PromptPad", "Command Prompt", "Prompt Buddy Helper", "prompt-buddy-dev"] {
+            assert_eq!(check(&list, name), Verdict::accept("matches no deny pattern".to_string()), "{}", name);
+        }
+    }
+}
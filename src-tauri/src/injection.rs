@@ -0,0 +1,508 @@
+// How prompt text reaches the target app once it has focus.
+//
+// Typing goes through `enigo.text`, one key event per character. That is
+// reliable but slow for long prompts and can fight with editor autocomplete,
+// so prompts can instead be pasted: the clipboard is saved, the prompt is put
+// there and pasted, and the original clipboard comes back after a delay.
+//
+// Newlines need care when typing: a typed `\n` is an Enter press, which sends
+// a half-typed message in most chat boxes. The newline policy decides what a
+// line break becomes, globally or per target app.
+//
+// A prompt's key macro, if it has one, is played around the text.
+//
+// In a Wayland session enigo only reaches XWayland windows, so text goes
+// through the compositor's virtual keyboard instead, see `deliver_wayland`.
+
+use enigo::{Direction, Enigo, Key, Keyboard};
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::key_macro::{self, Action};
+use crate::routing::Route;
+#[cfg(target_os = "linux")]
+use crate::virtual_keyboard::VirtualKeyboard;
+use crate::AppSettings;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Strategy {
+    Type,
+    Paste,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NewlinePolicy {
+    // Press Enter for each line break, exactly like the text says.
+    Enter,
+    // Press Shift+Enter, which inserts a line break in chat inputs.
+    ShiftEnter,
+    // Paste the whole prompt as one block whatever the injection strategy.
+    Paste,
+    // Join the lines with single spaces.
+    Spaces,
+}
+
+impl NewlinePolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "enter" => Some(Self::Enter),
+            "shift-enter" => Some(Self::ShiftEnter),
+            "paste" => Some(Self::Paste),
+            "spaces" => Some(Self::Spaces),
+            _ => None,
+        }
+    }
+}
+
+// Clipboard contents to put back once a paste has landed. A paste that starts
+// while an earlier restore is still waiting reuses its saved contents, so
+// rapid injections don't "restore" the previous prompt instead.
+struct PendingRestore {
+    original: Option<String>,
+    pasted: String,
+    generation: u64,
+}
+
+// Bounds `validate` holds the numeric settings to. Restoring the clipboard
+// sooner than this can beat the paste itself; later ones just mean a stale
+// clipboard, and a threshold above the longest prompt anyone writes is "type".
+pub const MAX_PASTE_THRESHOLD: usize = 100_000;
+pub const MIN_RESTORE_DELAY_MS: u64 = 100;
+pub const MAX_RESTORE_DELAY_MS: u64 = 10_000;
+
+static PENDING_RESTORE: Lazy<Mutex<Option<PendingRestore>>> = Lazy::new(|| Mutex::new(None));
+static RESTORE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+// Apply what the routing rules picked for the current app on top of the
+// global settings. A rule's newline policy is more specific than the per-app
+// overrides, so it replaces them.
+pub fn with_route(mut settings: AppSettings, route: &Route) -> AppSettings {
+    if let Some(strategy) = &route.injection_strategy {
+        settings.injection_strategy = strategy.clone();
+    }
+    if let Some(policy) = &route.newline_policy {
+        settings.newline_policy = policy.clone();
+        settings.newline_overrides.clear();
+    }
+    settings
+}
+
+// Pick a strategy from the `injectionStrategy` setting: "type", "paste", or
+// "auto", which pastes anything longer than `pasteThreshold` characters.
+pub fn choose(settings: &AppSettings, text: &str) -> Strategy {
+    match settings.injection_strategy.as_str() {
+        "type" => Strategy::Type,
+        "paste" => Strategy::Paste,
+        _ => {
+            if text.chars().count() > settings.paste_threshold {
+                Strategy::Paste
+            } else {
+                Strategy::Type
+            }
+        }
+    }
+}
+
+// The newline policy for `target_app`: a `newlineOverrides` entry matching the
+// app name (case-insensitively) wins over the global `newlinePolicy`. Unknown
+// values are rejected by `validate`, so they only come from a hand-edited
+// file; they get Shift+Enter, since pressing Enter is what the policy is there
+// to avoid.
+pub fn newline_policy(settings: &AppSettings, target_app: Option<&str>) -> NewlinePolicy {
+    let configured = target_app
+        .and_then(|app| {
+            settings
+                .newline_overrides
+                .iter()
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case(app.trim()))
+                .map(|(_, policy)| policy.as_str())
+        })
+        .unwrap_or(settings.newline_policy.as_str());
+
+    NewlinePolicy::parse(configured).unwrap_or_else(|| {
+        println!("⚠️ Unknown newline policy '{}', pressing Shift+Enter", configured);
+        NewlinePolicy::ShiftEnter
+    })
+}
+
+// Check the injection settings before they are saved.
+pub fn validate(settings: &AppSettings) -> Result<(), String> {
+    let mut errors = Vec::new();
+    if !["type", "paste", "auto"].contains(&settings.injection_strategy.as_str()) {
+        errors.push(format!("unknown injection strategy '{}'", settings.injection_strategy));
+    }
+    if settings.paste_threshold > MAX_PASTE_THRESHOLD {
+        errors.push(format!("paste threshold must be at most {} characters", MAX_PASTE_THRESHOLD));
+    }
+    if !(MIN_RESTORE_DELAY_MS..=MAX_RESTORE_DELAY_MS).contains(&settings.clipboard_restore_delay_ms) {
+        errors.push(format!(
+            "clipboard restore delay must be between {} and {} ms",
+            MIN_RESTORE_DELAY_MS, MAX_RESTORE_DELAY_MS
+        ));
+    }
+    if NewlinePolicy::parse(&settings.newline_policy).is_none() {
+        errors.push(format!("unknown newline policy '{}'", settings.newline_policy));
+    }
+    let mut overrides: Vec<_> = settings.newline_overrides.iter().collect();
+    overrides.sort();
+    for (app_name, policy) in overrides {
+        if app_name.trim().is_empty() {
+            errors.push("newline override with no app name".to_string());
+        }
+        if NewlinePolicy::parse(policy).is_none() {
+            errors.push(format!("unknown newline policy '{}' for {}", policy, app_name));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Invalid settings: {}", errors.join("; ")))
+    }
+}
+
+fn collapse_newlines(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn deliver(
+    app: &AppHandle,
+    enigo: &mut Enigo,
+    text: &str,
+    settings: &AppSettings,
+    target_app: Option<&str>,
+    key_macro: &[Action],
+) -> Result<(), String> {
+    let (before, after) = key_macro::split_at_text(key_macro);
+    if !key_macro.is_empty() {
+        println!("🎹 Running key macro: {} action(s) before, {} after the text", before.len(), after.len());
+    }
+    key_macro::run(enigo, before)?;
+    deliver_text(app, enigo, text, settings, target_app)?;
+    key_macro::run(enigo, after)
+}
+
+// The text as it will be delivered, with the newline policy and strategy for
+// delivering it.
+fn plan(text: &str, settings: &AppSettings, target_app: Option<&str>) -> (String, NewlinePolicy, Strategy) {
+    let text = text.replace("\r\n", "\n");
+    let multiline = text.contains('\n');
+    let policy = if multiline { newline_policy(settings, target_app) } else { NewlinePolicy::Enter };
+    if multiline {
+        println!("↩️  Newline policy for {}: {:?}", target_app.unwrap_or("unknown app"), policy);
+    }
+
+    let text = match policy {
+        NewlinePolicy::Spaces => collapse_newlines(&text),
+        _ => text,
+    };
+    let strategy = match policy {
+        NewlinePolicy::Paste => Strategy::Paste,
+        _ => choose(settings, &text),
+    };
+    (text, policy, strategy)
+}
+
+fn deliver_text(
+    app: &AppHandle,
+    enigo: &mut Enigo,
+    text: &str,
+    settings: &AppSettings,
+    target_app: Option<&str>,
+) -> Result<(), String> {
+    let (text, policy, strategy) = plan(text, settings, target_app);
+    let restore_delay = Duration::from_millis(settings.clipboard_restore_delay_ms);
+
+    match strategy {
+        Strategy::Type => {
+            println!("⌨️  Typing {} characters", text.chars().count());
+            match policy {
+                NewlinePolicy::ShiftEnter => type_with_shift_enter(enigo, &text),
+                _ => enigo.text(&text).map_err(|e| format!("❌ Failed to inject text: {}", e)),
+            }
+        }
+        // Pasted line breaks never press Enter, so every policy is safe here.
+        Strategy::Paste => {
+            println!("📋 Pasting {} characters", text.chars().count());
+            paste(app, &text, restore_delay, || send_paste_chord(enigo))
+        }
+    }
+}
+
+// Deliver `text` in a Wayland session. Without a virtual keyboard the prompt
+// is pasted, with enigo sending the paste shortcut, which reaches XWayland
+// windows (Electron apps by default) but not native ones. Key macros need the
+// virtual keyboard: played through enigo they'd go to whichever XWayland
+// window last had focus, if any.
+#[cfg(target_os = "linux")]
+pub fn deliver_wayland(
+    app: &AppHandle,
+    text: &str,
+    settings: &AppSettings,
+    target_app: Option<&str>,
+    key_macro: &[Action],
+) -> Result<(), String> {
+    let Some(mut keyboard) = VirtualKeyboard::connect() else {
+        if !key_macro.is_empty() {
+            return Err("❌ Key macros are unsupported on this session: the compositor offers no virtual keyboard".to_string());
+        }
+        let (text, _, _) = plan(text, settings, target_app);
+        let restore_delay = Duration::from_millis(settings.clipboard_restore_delay_ms);
+        println!("📋 No virtual keyboard in this session, pasting {} characters instead", text.chars().count());
+        return paste(app, &text, restore_delay, || {
+            let mut enigo = Enigo::new(&enigo::Settings::default())
+                .map_err(|e| format!("❌ Failed to create input simulator: {}", e))?;
+            send_paste_chord(&mut enigo)
+        });
+    };
+
+    let (before, after) = key_macro::split_at_text(key_macro);
+    if !key_macro.is_empty() {
+        println!("🎹 Running key macro on the virtual keyboard: {} action(s) before, {} after the text", before.len(), after.len());
+    }
+    key_macro::run_virtual(&mut keyboard, before)?;
+    deliver_virtual_text(app, &mut keyboard, text, settings, target_app)?;
+    key_macro::run_virtual(&mut keyboard, after)
+}
+
+#[cfg(target_os = "linux")]
+fn deliver_virtual_text(
+    app: &AppHandle,
+    keyboard: &mut VirtualKeyboard,
+    text: &str,
+    settings: &AppSettings,
+    target_app: Option<&str>,
+) -> Result<(), String> {
+    let (text, policy, strategy) = plan(text, settings, target_app);
+    let restore_delay = Duration::from_millis(settings.clipboard_restore_delay_ms);
+
+    match strategy {
+        Strategy::Type => {
+            println!("⌨️  Typing {} characters on the virtual keyboard", text.chars().count());
+            keyboard.type_text(&text, policy == NewlinePolicy::ShiftEnter)
+        }
+        Strategy::Paste => {
+            println!("📋 Pasting {} characters", text.chars().count());
+            paste(app, &text, restore_delay, || keyboard.paste_chord())
+        }
+    }
+}
+
+fn type_with_shift_enter(enigo: &mut Enigo, text: &str) -> Result<(), String> {
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            enigo
+                .key(Key::Shift, Direction::Press)
+                .map_err(|e| format!("❌ Failed to press Shift+Enter: {}", e))?;
+            let result = enigo.key(Key::Return, Direction::Click);
+            let release = enigo.key(Key::Shift, Direction::Release);
+            result
+                .and(release)
+                .map_err(|e| format!("❌ Failed to press Shift+Enter: {}", e))?;
+        }
+        if !line.is_empty() {
+            enigo.text(line).map_err(|e| format!("❌ Failed to inject text: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+fn paste(
+    app: &AppHandle,
+    text: &str,
+    restore_delay: Duration,
+    send_chord: impl FnOnce() -> Result<(), String>,
+) -> Result<(), String> {
+    // Only text can be saved through the clipboard plugin; if the clipboard
+    // held something else (or nothing) there is nothing to restore.
+    let original = match PENDING_RESTORE.lock().unwrap().take() {
+        Some(pending) => pending.original,
+        None => app.clipboard().read_text().ok(),
+    };
+
+    if let Err(e) = app.clipboard().write_text(text.to_string()) {
+        restore(app, original);
+        return Err(format!("❌ Failed to put prompt on the clipboard: {}", e));
+    }
+
+    // Give the clipboard a moment to propagate before the target reads it.
+    std::thread::sleep(Duration::from_millis(50));
+    if let Err(e) = send_chord() {
+        restore(app, original);
+        return Err(e);
+    }
+
+    let generation = RESTORE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    *PENDING_RESTORE.lock().unwrap() = Some(PendingRestore {
+        original,
+        pasted: text.to_string(),
+        generation,
+    });
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(restore_delay);
+        let pending = {
+            let mut pending = PENDING_RESTORE.lock().unwrap();
+            match pending.as_ref() {
+                Some(p) if p.generation == generation => pending.take(),
+                // A newer paste has taken over the restore.
+                _ => return,
+            }
+        };
+        let Some(pending) = pending else { return };
+
+        // Leave the clipboard alone if the user copied something meanwhile.
+        if app.clipboard().read_text().ok().as_deref() == Some(pending.pasted.as_str()) {
+            restore(&app, pending.original);
+        }
+    });
+
+    Ok(())
+}
+
+fn restore(app: &AppHandle, original: Option<String>) {
+    if let Some(original) = original {
+        match app.clipboard().write_text(original) {
+            Ok(_) => println!("📋 Restored previous clipboard contents"),
+            Err(e) => println!("⚠️ Failed to restore clipboard: {}", e),
+        }
+    }
+}
+
+fn send_paste_chord(enigo: &mut Enigo) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let modifier = Key::Meta;
+    #[cfg(not(target_os = "macos"))]
+    let modifier = Key::Control;
+
+    enigo
+        .key(modifier, Direction::Press)
+        .map_err(|e| format!("❌ Failed to send paste shortcut: {}", e))?;
+    let result = enigo.key(Key::Unicode('v'), Direction::Click);
+    // Always release the modifier, even if the V press failed.
+    let release = enigo.key(modifier, Direction::Release);
+
+    result
+        .and(release)
+        .map_err(|e| format!("❌ Failed to send paste shortcut: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings_with(policy: &str, overrides: &[(&str, &str)]) -> AppSettings {
+        AppSettings {
+            newline_policy: policy.to_string(),
+            newline_overrides: overrides.iter().map(|(app, policy)| (app.to_string(), policy.to_string())).collect(),
+            ..AppSettings::default()
+        }
+    }
+
+    #[test]
+    fn overrides_match_app_names_case_insensitively() {
+        let settings = settings_with("shift-enter", &[(" cursor ", "paste"), ("Terminal", "enter")]);
+        assert_eq!(newline_policy(&settings, Some("Cursor")), NewlinePolicy::Paste);
+        assert_eq!(newline_policy(&settings, Some("terminal")), NewlinePolicy::Enter);
+        assert_eq!(newline_policy(&settings, Some("Safari")), NewlinePolicy::ShiftEnter);
+        assert_eq!(newline_policy(&settings, None), NewlinePolicy::ShiftEnter);
+    }
+
+    #[test]
+    fn unknown_policies_never_press_enter() {
+        assert_eq!(newline_policy(&settings_with("shift+enter", &[]), None), NewlinePolicy::ShiftEnter);
+        let settings = settings_with("spaces", &[("Cursor", "Enter!")]);
+        assert_eq!(newline_policy(&settings, Some("Cursor")), NewlinePolicy::ShiftEnter);
+    }
+
+    #[test]
+    fn validate_rejects_unknown_policies() {
+        assert_eq!(validate(&settings_with("paste", &[("Cursor", "spaces")])), Ok(()));
+        assert_eq!(
+            validate(&settings_with("shift+enter", &[("Cursor", "Enter"), (" ", "paste")])),
+            Err("Invalid settings: unknown newline policy 'shift+enter'; newline override with no app name; unknown newline policy 'Enter' for Cursor".to_string())
+        );
+    }
+
+    #[test]
+    fn validate_checks_strategy_and_bounds() {
+        let settings = AppSettings {
+            injection_strategy: "clipboard".to_string(),
+            paste_threshold: MAX_PASTE_THRESHOLD + 1,
+            clipboard_restore_delay_ms: 0,
+            ..AppSettings::default()
+        };
+        assert_eq!(
+            validate(&settings),
+            Err("Invalid settings: unknown injection strategy 'clipboard'; paste threshold must be at most 100000 characters; clipboard restore delay must be between 100 and 10000 ms".to_string())
+        );
+
+        let settings = AppSettings {
+            injection_strategy: "paste".to_string(),
+            paste_threshold: 0,
+            clipboard_restore_delay_ms: MAX_RESTORE_DELAY_MS,
+            ..AppSettings::default()
+        };
+        assert_eq!(validate(&settings), Ok(()));
+        assert!(validate(&AppSettings { clipboard_restore_delay_ms: MAX_RESTORE_DELAY_MS + 1, ..AppSettings::default() }).is_err());
+    }
+
+    #[test]
+    fn auto_pastes_only_above_the_threshold() {
+        let settings = AppSettings { paste_threshold: 5, ..AppSettings::default() };
+        assert_eq!(choose(&settings, "12345"), Strategy::Type);
+        assert_eq!(choose(&settings, "123456"), Strategy::Paste);
+        // Characters, not bytes.
+        assert_eq!(choose(&settings, "ééééé"), Strategy::Type);
+
+        let typing = AppSettings { injection_strategy: "type".to_string(), ..settings };
+        assert_eq!(choose(&typing, &"x".repeat(1_000)), Strategy::Type);
+        let pasting = AppSettings { injection_strategy: "paste".to_string(), ..typing };
+        assert_eq!(choose(&pasting, "x"), Strategy::Paste);
+    }
+
+    #[test]
+    fn plan_applies_the_newline_policy_before_choosing() {
+        let settings = AppSettings { paste_threshold: 10, ..settings_with("spaces", &[("Cursor", "paste")]) };
+        // Collapsing "a\r\nb" leaves three characters, so it is typed.
+        assert_eq!(plan("a\r\nb", &settings, None), ("a b".to_string(), NewlinePolicy::Spaces, Strategy::Type));
+        // A paste policy pastes however short the prompt.
+        assert_eq!(plan("a\nb", &settings, Some("Cursor")), ("a\nb".to_string(), NewlinePolicy::Paste, Strategy::Paste));
+        // Single lines ignore the policy.
+        assert_eq!(plan("a b", &settings, Some("Cursor")), ("a b".to_string(), NewlinePolicy::Enter, Strategy::Type));
+    }
+
+    #[test]
+    fn routes_override_strategy_and_newlines() {
+        let settings = settings_with("shift-enter", &[("Cursor", "paste")]);
+        let route = Route {
+            injection_strategy: Some("paste".to_string()),
+            newline_policy: Some("spaces".to_string()),
+            ..Route::default()
+        };
+        let routed = with_route(settings, &route);
+        assert_eq!(routed.injection_strategy, "paste");
+        // The rule's policy replaces the per-app overrides as well.
+        assert_eq!(newline_policy(&routed, Some("Cursor")), NewlinePolicy::Spaces);
+        assert_eq!(plan("a\nb", &routed, Some("Cursor")), ("a b".to_string(), NewlinePolicy::Spaces, Strategy::Paste));
+
+        let unrouted = with_route(settings_with("enter", &[]), &Route::default());
+        assert_eq!((unrouted.injection_strategy.as_str(), unrouted.newline_policy.as_str()), ("auto", "enter"));
+    }
+
+    #[test]
+    fn collapsed_lines_are_trimmed_and_joined() {
+        assert_eq!(collapse_newlines("  Fix this:\n\n  - the parser \n- the tests\n"), "Fix this: - the parser - the tests");
+        assert_eq!(collapse_newlines("one line"), "one line");
+        assert_eq!(collapse_newlines("\n\n"), "");
+    }
+}
@@ -0,0 +1,184 @@
+// Fill-in form for `{{input:...}}` template variables.
+//
+// When a prompt needs user input, injection waits while a small `variables`
+// window asks for every value. The form fetches the pending request with
+// `get_pending_inputs` and answers with `submit_inputs` or `cancel_inputs`.
+// Submitted values are remembered per variable name, so the form can offer
+// them again next time.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_store::StoreExt;
+
+pub const INPUTS_STORE: &str = "inputs.json";
+pub const FORM_WINDOW: &str = "variables";
+
+// Most recent values kept per variable name.
+const MAX_HISTORY: usize = 10;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InputField {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    // Past values, most recent first.
+    pub history: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InputRequest {
+    pub id: String,
+    pub fields: Vec<InputField>,
+}
+
+struct Pending {
+    request: InputRequest,
+    // `None` means the user cancelled.
+    reply: Sender<Option<HashMap<String, String>>>,
+}
+
+static PENDING: Lazy<Mutex<Option<Pending>>> = Lazy::new(|| Mutex::new(None));
+
+fn load_history(app: &AppHandle) -> Result<HashMap<String, Vec<String>>, String> {
+    let store = app
+        .store(INPUTS_STORE)
+        .map_err(|e| format!("Failed to load inputs store: {}", e))?;
+
+    match store.get("history") {
+        Some(value) => serde_json::from_value(value).map_err(|e| format!("Failed to parse input history: {}", e)),
+        None => Ok(HashMap::new()),
+    }
+}
+
+fn remember(app: &AppHandle, values: &HashMap<String, String>) -> Result<(), String> {
+    let mut history = load_history(app)?;
+    for (name, value) in values {
+        if value.is_empty() {
+            continue;
+        }
+        let entries = history.entry(name.clone()).or_default();
+        entries.retain(|existing| existing != value);
+        entries.insert(0, value.clone());
+        entries.truncate(MAX_HISTORY);
+    }
+
+    let store = app
+        .store(INPUTS_STORE)
+        .map_err(|e| format!("Failed to load inputs store: {}", e))?;
+    let value = serde_json::to_value(&history).map_err(|e| format!("Failed to serialize input history: {}", e))?;
+    store.set("history", value);
+    store.save().map_err(|e| format!("Failed to save input history: {}", e))
+}
+
+// Show the form for `variables` (name, default) and wait for the user.
+// Returns `Ok(None)` if the form was cancelled.
+pub async fn request(app: &AppHandle, variables: Vec<(String, Option<String>)>) -> Result<Option<HashMap<String, String>>, String> {
+    let history = load_history(app).unwrap_or_else(|e| {
+        println!("⚠️ {}", e);
+        HashMap::new()
+    });
+
+    let fields = variables
+        .into_iter()
+        .map(|(name, default)| {
+            let history = history.get(&name).cloned().unwrap_or_default();
+            InputField { name, default, history }
+        })
+        .collect::<Vec<_>>();
+    let field_count = fields.len();
+    let request = InputRequest { id: uuid::Uuid::new_v4().to_string(), fields };
+
+    let (reply, receiver) = mpsc::channel();
+    // Only one form at a time: a newer request cancels the one still open.
+    if let Some(previous) = PENDING.lock().unwrap().replace(Pending { request: request.clone(), reply }) {
+        println!("⚠️ Replacing unanswered input request {}", previous.request.id);
+        let _ = previous.reply.send(None);
+    }
+
+    show_form(app, &request, field_count)?;
+
+    tauri::async_runtime::spawn_blocking(move || receiver.recv().ok().flatten())
+        .await
+        .map_err(|e| format!("Failed to wait for template input: {}", e))
+}
+
+fn show_form(app: &AppHandle, request: &InputRequest, field_count: usize) -> Result<(), String> {
+    println!("📝 Showing input form for {} variable(s)", field_count);
+
+    if let Some(window) = app.get_webview_window(FORM_WINDOW) {
+        println!("Existing input form found, showing and setting focus.");
+        let _ = window.center();
+        let _ = window.show();
+        let _ = window.set_focus();
+        if let Err(e) = window.emit("input-form-requested", request.clone()) {
+            println!("Error emitting input-form-requested event: {}", e);
+        }
+        return Ok(());
+    }
+
+    // The form pulls the request with `get_pending_inputs` once it has loaded,
+    // so there is nothing to emit to a freshly created window.
+    let height = (120.0 + 64.0 * field_count as f64).min(560.0);
+    let window = WebviewWindowBuilder::new(app, FORM_WINDOW, WebviewUrl::App("index.html?variables".into()))
+        .title("Fill in prompt")
+        .decorations(false)
+        .always_on_top(true)
+        .resizable(false)
+        .visible(false)
+        .center()
+        .inner_size(360.0, height)
+        .build()
+        .map_err(|e| format!("Failed to open input form: {}", e))?;
+
+    let _ = window.show();
+    let _ = window.set_focus();
+    Ok(())
+}
+
+fn hide_form(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(FORM_WINDOW) {
+        let _ = window.hide();
+    }
+}
+
+pub fn pending() -> Option<InputRequest> {
+    PENDING.lock().unwrap().as_ref().map(|pending| pending.request.clone())
+}
+
+// Take the pending request if `id` still refers to it, so a stale form can't
+// answer a newer request.
+fn take(id: &str) -> Result<Pending, String> {
+    let mut pending = PENDING.lock().unwrap();
+    match pending.as_ref() {
+        Some(current) if current.request.id == id => Ok(pending.take().unwrap()),
+        _ => Err("This input request is no longer pending".to_string()),
+    }
+}
+
+pub fn submit(app: &AppHandle, id: &str, values: HashMap<String, String>) -> Result<(), String> {
+    let pending = take(id)?;
+    hide_form(app);
+
+    if let Err(e) = remember(app, &values) {
+        println!("⚠️ Failed to remember input values: {}", e);
+    }
+
+    pending
+        .reply
+        .send(Some(values))
+        .map_err(|_| "Injection is no longer waiting for input".to_string())
+}
+
+pub fn cancel(app: &AppHandle, id: &str) -> Result<(), String> {
+    let pending = take(id)?;
+    hide_form(app);
+    println!("🚫 Input form cancelled");
+    let _ = pending.reply.send(None);
+    Ok(())
+}
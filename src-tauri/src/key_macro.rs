@@ -0,0 +1,367 @@
+// Key macros: short key-sequence scripts stored with a prompt and played
+// around its text, so one shortcut can clear an input, type the prompt and
+// submit it.
+//
+// A script is a sequence of `{...}` actions:
+//
+//   {enter} {tab} {esc} {a}     press a single key
+//   {ctrl+a} {cmd+shift+k}      press a key with modifiers held
+//   {mod+a}                     `mod` is Cmd on macOS and Ctrl elsewhere
+//   {selectall}                 shorthand for {mod+a}
+//   {sleep 200}                 wait 200 ms
+//   {text}                      where the prompt text goes
+//
+// Without `{text}` the whole script runs after the prompt; with it, actions
+// before `{text}` run first, e.g. `{selectall}{backspace}{text}{enter}`. Text
+// between actions is typed as-is after trimming surrounding whitespace, so
+// use `{space}` for a deliberate leading or trailing space. `\{` and `\}` type
+// a literal brace.
+
+use enigo::{Direction, Enigo, Key, Keyboard};
+use std::time::Duration;
+
+#[cfg(target_os = "linux")]
+use crate::virtual_keyboard::VirtualKeyboard;
+
+// Longest single `{sleep}`, so a typo can't freeze injection for minutes.
+const MAX_SLEEP_MS: u64 = 10_000;
+// Pause between actions so the target app handles each one before the next.
+const ACTION_GAP: Duration = Duration::from_millis(30);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Text,
+    Type(String),
+    Chord { modifiers: Vec<Key>, key: Key },
+    Sleep(u64),
+}
+
+#[cfg(target_os = "macos")]
+const PRIMARY_MODIFIER: Key = Key::Meta;
+#[cfg(not(target_os = "macos"))]
+const PRIMARY_MODIFIER: Key = Key::Control;
+
+fn parse_modifier(name: &str) -> Option<Key> {
+    match name {
+        "ctrl" | "control" => Some(Key::Control),
+        "alt" | "option" | "opt" => Some(Key::Alt),
+        "shift" => Some(Key::Shift),
+        "cmd" | "command" | "meta" | "super" | "win" => Some(Key::Meta),
+        "mod" => Some(PRIMARY_MODIFIER),
+        _ => None,
+    }
+}
+
+fn parse_key(name: &str) -> Option<Key> {
+    let key = match name {
+        "enter" | "return" => Key::Return,
+        "tab" => Key::Tab,
+        "esc" | "escape" => Key::Escape,
+        "backspace" => Key::Backspace,
+        "delete" | "del" => Key::Delete,
+        "space" => Key::Space,
+        "up" => Key::UpArrow,
+        "down" => Key::DownArrow,
+        "left" => Key::LeftArrow,
+        "right" => Key::RightArrow,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" | "pgup" => Key::PageUp,
+        "pagedown" | "pgdn" => Key::PageDown,
+        "plus" => Key::Unicode('+'),
+        "f1" => Key::F1,
+        "f2" => Key::F2,
+        "f3" => Key::F3,
+        "f4" => Key::F4,
+        "f5" => Key::F5,
+        "f6" => Key::F6,
+        "f7" => Key::F7,
+        "f8" => Key::F8,
+        "f9" => Key::F9,
+        "f10" => Key::F10,
+        "f11" => Key::F11,
+        "f12" => Key::F12,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Key::Unicode(c),
+                _ => return parse_modifier(name),
+            }
+        }
+    };
+    Some(key)
+}
+
+fn parse_action(body: &str) -> Result<Action, String> {
+    let body = body.trim().to_lowercase();
+    if body.is_empty() {
+        return Err("empty action".to_string());
+    }
+
+    let mut words = body.split_whitespace();
+    match (words.next(), words.next(), words.next()) {
+        (Some("text"), None, _) => return Ok(Action::Text),
+        (Some("selectall"), None, _) => {
+            return Ok(Action::Chord { modifiers: vec![PRIMARY_MODIFIER], key: Key::Unicode('a') });
+        }
+        (Some("sleep"), None, _) => {
+            return Err("sleep needs a duration in milliseconds, e.g. {sleep 200}".to_string());
+        }
+        (Some("sleep"), Some(ms), None) => {
+            return match ms.parse::<u64>() {
+                Ok(ms) if ms > 0 && ms <= MAX_SLEEP_MS => Ok(Action::Sleep(ms)),
+                Ok(_) => Err(format!("sleep must be between 1 and {} ms", MAX_SLEEP_MS)),
+                Err(_) => Err(format!("invalid sleep duration '{}'", ms)),
+            };
+        }
+        (Some(_), Some(_), _) if !body.contains('+') => return Err(format!("unexpected '{}'", body)),
+        _ => {}
+    }
+
+    let parts: Vec<&str> = body.split('+').map(str::trim).collect();
+    let (key_name, modifier_names) = parts.split_last().expect("split always yields a part");
+    if key_name.is_empty() || modifier_names.iter().any(|m| m.is_empty()) {
+        return Err("empty key in chord, use 'plus' for the + key".to_string());
+    }
+
+    let mut modifiers = Vec::new();
+    for name in modifier_names {
+        let modifier = parse_modifier(name).ok_or_else(|| format!("unknown modifier '{}'", name))?;
+        if modifiers.contains(&modifier) {
+            return Err(format!("modifier '{}' repeated", name));
+        }
+        modifiers.push(modifier);
+    }
+    let key = parse_key(key_name).ok_or_else(|| format!("unknown key '{}'", key_name))?;
+
+    Ok(Action::Chord { modifiers, key })
+}
+
+fn push_literal(actions: &mut Vec<Action>, literal: &mut String) {
+    let text = std::mem::take(literal);
+    let text = text.trim();
+    if !text.is_empty() {
+        actions.push(Action::Type(text.to_string()));
+    }
+}
+
+// Parse a script into actions. All problems are collected so the user sees
+// every mistake at once, as with template errors.
+pub fn parse(script: &str) -> Result<Vec<Action>, String> {
+    let mut actions = Vec::new();
+    let mut errors = Vec::new();
+    let mut literal = String::new();
+    let mut rest = script;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        let offset = script.len() - rest.len();
+        let brace = &rest[start..start + 1];
+        if rest[..start].ends_with('\\') {
+            literal.push_str(&rest[..start - 1]);
+            literal.push_str(brace);
+            rest = &rest[start + 1..];
+            continue;
+        }
+        literal.push_str(&rest[..start]);
+        if brace == "}" {
+            errors.push(format!("unmatched '}}' at position {}", offset + start));
+            rest = &rest[start + 1..];
+            continue;
+        }
+        push_literal(&mut actions, &mut literal);
+
+        let after_open = &rest[start + 1..];
+        let end = match after_open.find(['{', '}']) {
+            Some(end) if after_open[end..].starts_with('}') => end,
+            _ => {
+                errors.push(format!("unterminated '{{' at position {}", offset + start));
+                rest = &after_open[after_open.find('{').unwrap_or(after_open.len())..];
+                continue;
+            }
+        };
+
+        let body = &after_open[..end];
+        match parse_action(body) {
+            Ok(Action::Text) if actions.contains(&Action::Text) => {
+                errors.push("{text} can only appear once".to_string());
+            }
+            Ok(action) => actions.push(action),
+            Err(e) => errors.push(format!("{{{}}}: {}", body, e)),
+        }
+        rest = &after_open[end + 1..];
+    }
+    literal.push_str(rest);
+    push_literal(&mut actions, &mut literal);
+
+    if errors.is_empty() {
+        Ok(actions)
+    } else {
+        Err(format!("Invalid key macro: {}", errors.join("; ")))
+    }
+}
+
+// The actions to play before and after the prompt text.
+pub fn split_at_text(actions: &[Action]) -> (&[Action], &[Action]) {
+    match actions.iter().position(|a| *a == Action::Text) {
+        Some(index) => (&actions[..index], &actions[index + 1..]),
+        None => (&actions[..0], actions),
+    }
+}
+
+pub fn run(enigo: &mut Enigo, actions: &[Action]) -> Result<(), String> {
+    for action in actions {
+        std::thread::sleep(ACTION_GAP);
+        match action {
+            Action::Text => {}
+            Action::Type(text) => enigo
+                .text(text)
+                .map_err(|e| format!("❌ Key macro failed to type '{}': {}", text, e))?,
+            Action::Chord { modifiers, key } => press_chord(enigo, modifiers, *key)?,
+            Action::Sleep(ms) => std::thread::sleep(Duration::from_millis(*ms)),
+        }
+    }
+    Ok(())
+}
+
+// `run` for a Wayland session, where keys go through the virtual keyboard.
+#[cfg(target_os = "linux")]
+pub fn run_virtual(keyboard: &mut VirtualKeyboard, actions: &[Action]) -> Result<(), String> {
+    for action in actions {
+        std::thread::sleep(ACTION_GAP);
+        match action {
+            Action::Text => {}
+            Action::Type(text) => keyboard.type_text(text, false)?,
+            Action::Chord { modifiers, key } => keyboard.press_chord(modifiers, *key)?,
+            Action::Sleep(ms) => std::thread::sleep(Duration::from_millis(*ms)),
+        }
+    }
+    Ok(())
+}
+
+fn press_chord(enigo: &mut Enigo, modifiers: &[Key], key: Key) -> Result<(), String> {
+    let mut pressed = Vec::new();
+    let mut result = Ok(());
+    for modifier in modifiers {
+        result = enigo.key(*modifier, Direction::Press);
+        if result.is_err() {
+            break;
+        }
+        pressed.push(*modifier);
+    }
+    if result.is_ok() {
+        result = enigo.key(key, Direction::Click);
+    }
+
+    // Always release what was pressed, even if the key itself failed.
+    for modifier in pressed.iter().rev() {
+        let release = enigo.key(*modifier, Direction::Release);
+        result = result.and(release);
+    }
+
+    result.map_err(|e| format!("❌ Key macro failed to press {:?}: {}", key, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(modifiers: &[Key], key: Key) -> Action {
+        Action::Chord { modifiers: modifiers.to_vec(), key }
+    }
+
+    #[test]
+    fn parses_keys_and_chords() {
+        assert_eq!(parse("{enter}"), Ok(vec![chord(&[], Key::Return)]));
+        assert_eq!(parse("{ctrl+a}"), Ok(vec![chord(&[Key::Control], Key::Unicode('a'))]));
+        assert_eq!(parse("{mod+a}"), Ok(vec![chord(&[PRIMARY_MODIFIER], Key::Unicode('a'))]));
+        assert_eq!(parse("{selectall}"), parse("{mod+a}"));
+        assert_eq!(
+            parse("{ Cmd + Shift + K }"),
+            Ok(vec![chord(&[Key::Meta, Key::Shift], Key::Unicode('k'))])
+        );
+        assert_eq!(parse("{ctrl+plus}"), Ok(vec![chord(&[Key::Control], Key::Unicode('+'))]));
+    }
+
+    #[test]
+    fn parses_sleeps_up_to_the_cap() {
+        assert_eq!(parse("{sleep 200}"), Ok(vec![Action::Sleep(200)]));
+        assert_eq!(parse("{sleep 10000}"), Ok(vec![Action::Sleep(MAX_SLEEP_MS)]));
+    }
+
+    #[test]
+    fn splits_around_text() {
+        let actions = parse("{selectall}{backspace} {text} {enter}").unwrap();
+        assert_eq!(
+            actions,
+            vec![
+                chord(&[PRIMARY_MODIFIER], Key::Unicode('a')),
+                chord(&[], Key::Backspace),
+                Action::Text,
+                chord(&[], Key::Return),
+            ]
+        );
+        let (before, after) = split_at_text(&actions);
+        assert_eq!((before.len(), after.len()), (2, 1));
+
+        let actions = parse("{enter}").unwrap();
+        assert_eq!(split_at_text(&actions), (&[][..], &actions[..]));
+    }
+
+    #[test]
+    fn types_literal_text_and_escaped_braces() {
+        assert_eq!(
+            parse("  /clear {enter} thanks  "),
+            Ok(vec![
+                Action::Type("/clear".to_string()),
+                chord(&[], Key::Return),
+                Action::Type("thanks".to_string()),
+            ])
+        );
+        assert_eq!(parse(r"\{\{json\}\}{tab}"), Ok(vec![Action::Type("{{json}}".to_string()), chord(&[], Key::Tab)]));
+    }
+
+    #[test]
+    fn reports_brace_errors() {
+        assert_eq!(parse("{enter"), Err("Invalid key macro: unterminated '{' at position 0".to_string()));
+        assert_eq!(
+            parse("{tab}{enter{esc}"),
+            Err("Invalid key macro: unterminated '{' at position 5".to_string())
+        );
+        assert_eq!(parse("a}"), Err("Invalid key macro: unmatched '}' at position 1".to_string()));
+        assert_eq!(parse("{}"), Err("Invalid key macro: {}: empty action".to_string()));
+    }
+
+    #[test]
+    fn reports_unknown_keys_and_modifiers() {
+        assert_eq!(parse("{foo}"), Err("Invalid key macro: {foo}: unknown key 'foo'".to_string()));
+        assert_eq!(parse("{hyper+a}"), Err("Invalid key macro: {hyper+a}: unknown modifier 'hyper'".to_string()));
+        assert_eq!(parse("{ctrl+ctrl+a}"), Err("Invalid key macro: {ctrl+ctrl+a}: modifier 'ctrl' repeated".to_string()));
+        assert_eq!(
+            parse("{ctrl+}"),
+            Err("Invalid key macro: {ctrl+}: empty key in chord, use 'plus' for the + key".to_string())
+        );
+        assert_eq!(parse("{press a}"), Err("Invalid key macro: {press a}: unexpected 'press a'".to_string()));
+    }
+
+    #[test]
+    fn reports_bad_sleeps() {
+        assert_eq!(parse("{sleep 0}"), Err("Invalid key macro: {sleep 0}: sleep must be between 1 and 10000 ms".to_string()));
+        assert_eq!(
+            parse("{sleep 10001}"),
+            Err("Invalid key macro: {sleep 10001}: sleep must be between 1 and 10000 ms".to_string())
+        );
+        assert_eq!(parse("{sleep soon}"), Err("Invalid key macro: {sleep soon}: invalid sleep duration 'soon'".to_string()));
+        assert_eq!(
+            parse("{sleep}"),
+            Err("Invalid key macro: {sleep}: sleep needs a duration in milliseconds, e.g. {sleep 200}".to_string())
+        );
+    }
+
+    #[test]
+    fn collects_every_error() {
+        assert_eq!(
+            parse("{foo}{text}{text}{sleep 0}"),
+            Err("Invalid key macro: {foo}: unknown key 'foo'; {text} can only appear once; {sleep 0}: sleep must be between 1 and 10000 ms".to_string())
+        );
+    }
+}
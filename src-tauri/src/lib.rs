@@ -1,19 +1,63 @@
 use tauri::{AppHandle, Listener, Manager, Emitter};
+use std::collections::HashMap;
 use std::process::Command;
-use std::sync::Mutex;
-use once_cell::sync::Lazy;
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
-use enigo::{Enigo, Keyboard, Settings};
+use enigo::{Enigo, Settings};
 use tauri::{WebviewWindowBuilder, WebviewUrl, LogicalPosition};
 use tauri_plugin_dialog;
 use serde::{Deserialize, Serialize};
 use tauri_plugin_store::StoreExt;
 
+mod chords;
+mod events;
+mod focus;
+mod focus_history;
+mod ignore_list;
+mod injection;
+mod input_form;
+mod key_macro;
+mod migrations;
+mod prompts;
+mod recorder;
+mod routing;
+mod search;
+mod shortcuts;
+mod template;
+mod usage;
+#[cfg(target_os = "linux")]
+mod virtual_keyboard;
+mod window_system;
+mod wrappers;
+
+use focus_history::FocusEntry;
+use ignore_list::{IgnoreList, Verdict};
+use input_form::InputRequest;
+use key_macro::Action;
+use migrations::MigrationReport;
+use prompts::{Folder, Prompt, PromptDraft, PromptLibrary};
+use recorder::{KeyCombo, RecordedShortcut};
+use routing::{Route, RoutingRule};
+use search::SearchResult;
+use shortcuts::{Binding, BindingKind, ShortcutStatus};
+use usage::RankedPrompt;
+use window_system::WindowInfo;
+
+// Missing fields fall back to their defaults so that adding a setting doesn't
+// invalidate older settings files.
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(default, rename_all = "camelCase")]
 struct AppSettings {
     toggle_shortcut: String,
     target_mode: String, // "auto" or "manual"
     target_app_name: String, // specific app name when in manual mode
+    injection_strategy: String, // "type", "paste" or "auto"
+    paste_threshold: usize, // in auto mode, prompts longer than this many characters are pasted
+    clipboard_restore_delay_ms: u64, // how long a paste waits before restoring the clipboard
+    newline_policy: String, // "enter", "shift-enter", "paste" or "spaces"
+    newline_overrides: HashMap<String, String>, // app name -> newline policy for that app
+    leader_shortcut: String, // starts a chord, empty to disable chords
+    chord_timeout_ms: u64, // how long a chord waits for the next letter
+    routing_rules: Vec<RoutingRule>, // ordered overrides by app name or window title
+    ignore_list: IgnoreList, // app names never used as the target app
 }
 
 impl Default for AppSettings {
@@ -22,165 +66,44 @@ impl Default for AppSettings {
             toggle_shortcut: "alt+shift+space".to_string(),
             target_mode: "auto".to_string(),
             target_app_name: "".to_string(),
+            injection_strategy: "auto".to_string(),
+            paste_threshold: 200,
+            clipboard_restore_delay_ms: 500,
+            newline_policy: "shift-enter".to_string(),
+            newline_overrides: HashMap::new(),
+            leader_shortcut: "".to_string(),
+            chord_timeout_ms: 1500,
+            routing_rules: routing::default_rules(),
+            ignore_list: IgnoreList::default(),
         }
     }
 }
 
-// Store the name of the application that was active **before** the prompt bar
-// was shown. This lets us switch focus back to that application after the user
-// clicks a prompt pill so the text is inserted into the correct window.
-static LAST_APP_NAME: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
-
-// Store the name of the application that was active before the last one
-// This helps in case the last app is Prompt Buddy itself or DaVinci Resolve Electron window
-static PREVIOUS_APP_NAME: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
-
-// The name of our own application (used to detect if we're trying to inject into ourselves)
-static APP_NAME: &str = "Prompt Buddy";
-
-// Known problematic app names that might cause issues - these should be exact app names
-static PROBLEMATIC_APPS: [&str; 1] = ["Electron"];
-
-// Helper function to resolve Electron apps to their actual names
-#[cfg(target_os = "macos")]
-fn get_electron_app_name() -> Option<String> {
-    // Get the process ID of the frontmost Electron app
-    let pid_output = Command::new("osascript")
-        .arg("-e")
-        .arg("tell application \"System Events\" to get unix id of first application process whose name is \"Electron\"")
-        .output()
-        .ok()?;
-    
-    if !pid_output.status.success() {
-        return None;
-    }
-    
-    let pid = String::from_utf8_lossy(&pid_output.stdout).trim().to_string();
-    if pid.is_empty() {
-        return None;
+// The saved settings, `None` if nothing was saved yet. A document that
+// doesn't parse is an error rather than the defaults, so it is never saved
+// over without the user knowing.
+fn stored_settings(app: &AppHandle) -> Result<Option<AppSettings>, String> {
+    let store = app
+        .store("settings.json")
+        .map_err(|e| format!("Failed to load settings store: {}", e))?;
+    match store.get("settings") {
+        Some(settings) => serde_json::from_value::<AppSettings>(settings)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse settings.json: {}", e)),
+        None => Ok(None),
     }
-    
-    println!("🔍 Electron process PID: {}", pid);
-    
-    // Get the bundle path for that PID
-    let bundle_output = Command::new("osascript")
-        .arg("-e")
-        .arg(format!("tell application \"System Events\" to get application file of application process id {}", pid))
-        .output()
-        .ok()?;
-    
-    if !bundle_output.status.success() {
-        return None;
-    }
-    
-    let bundle_path = String::from_utf8_lossy(&bundle_output.stdout).trim().to_string();
-    println!("🔍 Bundle path: {}", bundle_path);
-    
-    // Special handling for known apps
-    if bundle_path.contains("DaVinci Resolve") {
-        println!("🔍 Detected DaVinci Resolve from bundle path");
-        return Some("DaVinci Resolve".to_string());
-    }
-    
-    if bundle_path.contains("Qoder") || bundle_path.contains("qoder") {
-        println!("🔍 Detected Qoder from bundle path");
-        return Some("Qoder".to_string());
-    }
-    
-    // Extract the application name from the bundle path
-    if !bundle_path.is_empty() {
-        let parts: Vec<&str> = bundle_path.split("/").collect();
-        if let Some(app_file) = parts.last() {
-            let app_name = app_file.replace(".app", "");
-            println!("🔍 Resolved Electron to app: {}", app_name);
-            return Some(app_name);
-        }
-    }
-    
-    None
-}
-
-#[cfg(not(target_os = "macos"))]
-fn get_electron_app_name() -> Option<String> {
-    None
-}
-
-
-fn is_valid_app_name(name: &str) -> bool {
-    // Check for empty or very short names
-    if name.len() < 2 {
-        return false;
-    }
-    
-    // Check for our own app name (including variations)
-    if name.contains(APP_NAME) || name == "prompt-buddy" || name.contains("Prompt") {
-        return false;
-    }
-    
-    // Additional validation rules can be added here
-    true
-}
-
-// A utility function to check if an app name is problematic
-fn is_problematic_app(app_name: &str) -> bool {
-    println!("🔍 Checking if app is problematic: {}", app_name);
-    
-    // Check for our own app (including variations)
-    if app_name.contains(APP_NAME) || app_name == "prompt-buddy" || app_name.contains("Prompt") {
-        println!("⚠️ App is our own app");
-        return true;
-    }
-    
-    // Check for known problematic apps by exact match to avoid false positives
-    for name in PROBLEMATIC_APPS.iter() {
-        if app_name == *name {  // Exact match only
-            println!("⚠️ App exactly matches known problematic app: {}", name);
-            return true;
-        }
-    }
-    
-    // Filter out macOS system daemons and background processes
-    let system_processes = [
-        "universalaccessd", "Dock", "Finder", "SystemUIServer", "ControlCenter",
-        "WindowServer", "loginwindow", "kernel_task", "launchd", "syslogd",
-        "UserEventAgent", "cfprefsd", "distnoted", "NotificationCenter",
-        "Spotlight", "mds", "mdworker", "CoreServicesUIAgent", "AirPlayUIAgent"
-    ];
-    
-    for sys_proc in system_processes.iter() {
-        if app_name == *sys_proc {
-            println!("⚠️ App is a system process: {}", app_name);
-            return true;
-        }
-    }
-    
-    // Filter out apps ending with 'd' (likely daemons) or containing 'Agent'
-    if (app_name.ends_with("d") && app_name.len() > 3) || app_name.contains("Agent") {
-        println!("⚠️ App appears to be a daemon or agent: {}", app_name);
-        return true;
-    }
-    
-    // Not problematic
-    false
 }
 
 fn load_settings(app: &AppHandle) -> AppSettings {
-    match app.store("settings.json") {
-        Ok(store) => {
-            if let Some(settings) = store.get("settings") {
-                match serde_json::from_value::<AppSettings>(settings) {
-                    Ok(settings) => {
-                        println!("✅ Loaded settings: {:?}", settings);
-                        return settings;
-                    },
-                    Err(e) => {
-                        println!("⚠️  Failed to parse settings: {}", e);
-                    }
-                }
-            }
-        },
+    match stored_settings(app) {
+        Ok(Some(settings)) => {
+            println!("✅ Loaded settings: {:?}", settings);
+            return settings;
+        }
+        Ok(None) => {}
         Err(e) => {
-            println!("⚠️  Failed to load settings store: {}", e);
+            println!("⚠️  {}", e);
+            migrations::report_unreadable(app, "settings.json", &e);
         }
     }
     
@@ -189,115 +112,43 @@ fn load_settings(app: &AppHandle) -> AppSettings {
     default_settings
 }
 
-#[cfg(target_os = "macos")]
-fn get_frontmost_app() -> Option<String> {
-    // First get the process name to see if we need special handling
-    let name_output = Command::new("osascript")
-        .arg("-e")
-        .arg("tell application \"System Events\" to get name of application process 1 whose frontmost is true")
-        .output()
-        .ok()?;
-    
-    if !name_output.status.success() {
-        return None;
+// In manual target mode, the configured app that every prompt goes to. It has
+// to be running: we don't launch apps just to type into them.
+fn manual_target(settings: &AppSettings) -> Result<Option<String>, String> {
+    if settings.target_mode != "manual" {
+        return Ok(None);
     }
-    
-    let process_name = String::from_utf8_lossy(&name_output.stdout).trim().to_string();
-    println!("🔍 Frontmost process name: {}", process_name);
-    
-    // If it's Electron, we need to resolve to the actual parent application
-    if process_name == "Electron" {
-        println!("🔍 Detected Electron process, attempting to resolve parent app...");
-        
-        // Get the process ID of the frontmost app
-        let pid_output = Command::new("osascript")
-            .arg("-e")
-            .arg("tell application \"System Events\" to get unix id of application process 1 whose frontmost is true")
-            .output()
-            .ok()?;
-        
-        if pid_output.status.success() {
-            let pid = String::from_utf8_lossy(&pid_output.stdout).trim().to_string();
-            if !pid.is_empty() {
-                println!("🔍 Electron process PID: {}", pid);
-                
-                // Get the bundle path for that PID
-                let bundle_output = Command::new("osascript")
-                    .arg("-e")
-                    .arg(format!("tell application \"System Events\" to get application file of application process id {}", pid))
-                    .output()
-                    .ok()?;
-                
-                if bundle_output.status.success() {
-                    let bundle_path = String::from_utf8_lossy(&bundle_output.stdout).trim().to_string();
-                    println!("🔍 Bundle path: {}", bundle_path);
-                    
-                    // Special handling for known Electron apps
-                    if bundle_path.contains("DaVinci Resolve") {
-                        println!("🔍 Detected DaVinci Resolve from bundle path");
-                        return Some("DaVinci Resolve".to_string());
-                    }
-                    
-                    // Special handling for Qoder IDE
-                    if bundle_path.contains("Qoder") || bundle_path.contains("qoder") {
-                        println!("🔍 Detected Qoder IDE from bundle path");
-                        return Some("Qoder".to_string());
-                    }
-                    
-                    // Extract the application name from the bundle path
-                    if !bundle_path.is_empty() {
-                        let parts: Vec<&str> = bundle_path.split("/").collect();
-                        if let Some(app_file) = parts.last() {
-                            let app_name = app_file.replace(".app", "");
-                            println!("🔍 Resolved Electron to app: {}", app_name);
-                            return Some(app_name);
-                        }
-                    }
-                }
-            }
-        }
-        
-        // If we can't resolve the Electron app, return None to avoid activating random Electron processes
-        println!("⚠️ Could not resolve Electron process to parent app, returning None");
-        return None;
+
+    let app_name = settings.target_app_name.trim();
+    if app_name.is_empty() {
+        return Err("Manual target mode is on but no target app is set in settings".to_string());
     }
-    
-    // For non-Electron apps, validate the name and return it
-    if !process_name.is_empty() && is_valid_app_name(&process_name) {
-        println!("🔍 Using process name: {}", process_name);
-        return Some(process_name);
+    if !window_system::current().is_running(app_name) {
+        return Err(format!("Target app '{}' is not running", app_name));
     }
-    
-    println!("⚠️ Process name is empty or invalid");
-    None
+    Ok(Some(app_name.to_string()))
 }
 
-#[cfg(target_os = "macos")]
-fn activate_app(app_name: &str) -> bool {
-    // Check if this is a problematic app before activating
-    if is_problematic_app(app_name) {
-        println!("⚠️ Refusing to activate potentially problematic app: {}", app_name);
-        return false;
-    }
-    
-    // This is the safer approach - activate by name only, not by path
-    let cmd = format!("tell application \"{}\" to activate", app_name);
-    println!("🚀 Activating app with command: {}", cmd);
-    
-    Command::new("osascript")
-        .arg("-e")
-        .arg(cmd)
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
+// The settings with the current app's route applied, see `injection::with_route`.
+fn with_route(settings: AppSettings) -> AppSettings {
+    let route = focus_history::latest().map(|entry| entry.route).unwrap_or_default();
+    injection::with_route(settings, &route)
 }
 
-// Stub helpers for non-macOS platforms so compilation still succeeds.
-#[cfg(not(target_os = "macos"))]
-fn get_frontmost_app() -> Option<String> { None }
+// The app text is being injected into: the configured one in manual mode,
+// otherwise whatever was frontmost when the bar opened.
+fn current_target_app(settings: &AppSettings) -> Option<String> {
+    let app_name = settings.target_app_name.trim();
+    if settings.target_mode == "manual" && !app_name.is_empty() {
+        return Some(app_name.to_string());
+    }
+    focus_history::latest().map(|entry| entry.window.app_name)
+}
 
-#[cfg(not(target_os = "macos"))]
-fn activate_app(_app_name: &str) -> bool { false }
+fn activate_manual_target(app_name: &str) -> Result<(), String> {
+    println!("🎯 Manual target mode: activating {}", app_name);
+    focus::restore(window_system::current(), &[WindowInfo::new(app_name)], &focus::SystemClock)
+}
 
 #[derive(Clone, serde::Serialize)]
 struct PromptPayload {
@@ -310,15 +161,50 @@ async fn get_settings(app: tauri::AppHandle) -> Result<AppSettings, String> {
     Ok(load_settings(&app))
 }
 
+// What `save_settings` actually stored, and whether the shortcuts in it are
+// live. A shortcut that can't be registered is not saved.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SaveSettingsResult {
+    settings: AppSettings,
+    toggle_shortcut: ShortcutStatus,
+    // `None` when chords are disabled.
+    leader_shortcut: Option<ShortcutStatus>,
+}
+
 #[tauri::command]
-async fn save_settings(app: tauri::AppHandle, settings: AppSettings) -> Result<(), String> {
+async fn save_settings(app: tauri::AppHandle, mut settings: AppSettings) -> Result<SaveSettingsResult, String> {
     println!("💾 Saving settings: {:?}", settings);
+    routing::validate(&settings.routing_rules)?;
+    injection::validate(&settings)?;
+    if let Err(e) = stored_settings(&app) {
+        return Err(format!("{}. Not saving over it: fix the file or restore its .bak copy first", e));
+    }
+
+    // Switch the shortcut first; if that fails the previous one is still
+    // registered, so keep it in the saved settings too.
+    let toggle_shortcut = shortcuts::replace_toggle(&app, &settings.toggle_shortcut);
+    if !toggle_shortcut.is_active() {
+        println!("⚠️ Keeping previous toggle shortcut: {}", toggle_shortcut.error.as_deref().unwrap_or("unknown error"));
+        settings.toggle_shortcut = load_settings(&app).toggle_shortcut;
+    }
+    let leader_shortcut = chords::replace_leader(&app, &settings.leader_shortcut);
+    if let Some(status) = leader_shortcut.as_ref().filter(|s| !s.is_active()) {
+        println!("⚠️ Keeping previous chord leader: {}", status.error.as_deref().unwrap_or("unknown error"));
+        settings.leader_shortcut = load_settings(&app).leader_shortcut;
+    }
+    // Prompt shortcuts that clashed with the old toggle or leader may be free now.
+    match prompts::list(&app) {
+        Ok(library) => shortcuts::sync_prompts(&app, &library),
+        Err(e) => println!("⚠️ Failed to re-sync prompt shortcuts: {}", e),
+    }
     
     match app.store("settings.json") {
         Ok(store) => {
             match serde_json::to_value(&settings) {
                 Ok(settings_value) => {
-                    store.set("settings", settings_value);
+                    let previous_value = store.get("settings").unwrap_or(serde_json::Value::Null);
+                    store.set("settings", settings_value.clone());
                     
                     if let Err(e) = store.save() {
                         let error_msg = format!("Failed to save settings: {}", e);
@@ -327,7 +213,10 @@ async fn save_settings(app: tauri::AppHandle, settings: AppSettings) -> Result<(
                     }
                     
                     println!("✅ Settings saved successfully");
-                    Ok(())
+                    routing::set_rules(settings.routing_rules.clone());
+                    ignore_list::set(settings.ignore_list.clone());
+                    events::emit_settings_changed(&app, &previous_value, &settings_value);
+                    Ok(SaveSettingsResult { settings, toggle_shortcut, leader_shortcut })
                 },
                 Err(e) => {
                     let error_msg = format!("Failed to serialize settings: {}", e);
@@ -344,6 +233,120 @@ async fn save_settings(app: tauri::AppHandle, settings: AppSettings) -> Result<(
     }
 }
 
+// What the routing rules picked for the app the bar was opened over.
+#[tauri::command]
+async fn get_current_route() -> Result<Route, String> {
+    Ok(focus_history::latest().map(|entry| entry.route).unwrap_or_default())
+}
+
+// The windows the bar was recently opened over, most recent first. The first
+// one is the current target.
+#[tauri::command]
+async fn list_recent_targets() -> Result<Vec<FocusEntry>, String> {
+    Ok(focus_history::recent())
+}
+
+// Make recent target `id` the current one and bring it to the front, so the
+// next prompt goes there.
+#[tauri::command]
+async fn activate_target(app: AppHandle, id: u64) -> Result<FocusEntry, String> {
+    if manual_target(&load_settings(&app))?.is_some() {
+        return Err("Manual target mode is on, so prompts always go to the configured app".to_string());
+    }
+    let entry = focus_history::promote(id).ok_or_else(|| format!("Unknown target #{}", id))?;
+    focus::restore(window_system::current(), std::slice::from_ref(&entry.window), &focus::SystemClock)?;
+    Ok(entry)
+}
+
+// Whether the saved ignore list lets `name` be the target app, and which rule
+// decided.
+#[tauri::command]
+async fn test_target_app(app: tauri::AppHandle, name: String) -> Result<Verdict, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Enter an app name to test".to_string());
+    }
+    Ok(ignore_list::check(&load_settings(&app).ignore_list, name))
+}
+
+#[tauri::command]
+async fn get_shortcut_status() -> Result<Vec<ShortcutStatus>, String> {
+    Ok(shortcuts::status())
+}
+
+// Capture the next key combination for the toggle shortcut, the chord leader
+// or a prompt. Resolves with `None` if the recording was cancelled.
+#[tauri::command]
+async fn record_shortcut(kind: BindingKind, prompt_id: Option<String>) -> Result<Option<RecordedShortcut>, String> {
+    recorder::record(Binding::new(kind, prompt_id)).await
+}
+
+#[tauri::command]
+async fn submit_recorded_keys(keys: KeyCombo) -> Result<(), String> {
+    recorder::submit(keys)
+}
+
+#[tauri::command]
+async fn cancel_shortcut_recording() -> Result<(), String> {
+    recorder::cancel();
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_migration_status() -> Result<Vec<MigrationReport>, String> {
+    Ok(migrations::reports())
+}
+
+#[tauri::command]
+async fn list_prompts(app: tauri::AppHandle) -> Result<PromptLibrary, String> {
+    prompts::list(&app)
+}
+
+#[tauri::command]
+async fn search_prompts(app: tauri::AppHandle, query: String, limit: Option<usize>) -> Result<Vec<SearchResult>, String> {
+    let library = prompts::list(&app)?;
+    Ok(search::search(&library, &query, limit.unwrap_or(50)))
+}
+
+#[tauri::command]
+async fn create_prompt(app: tauri::AppHandle, prompt: PromptDraft) -> Result<Prompt, String> {
+    prompts::create(&app, prompt)
+}
+
+#[tauri::command]
+async fn update_prompt(app: tauri::AppHandle, id: String, prompt: PromptDraft) -> Result<Prompt, String> {
+    prompts::update(&app, &id, prompt)
+}
+
+#[tauri::command]
+async fn delete_prompt(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    prompts::delete(&app, &id)
+}
+
+#[tauri::command]
+async fn move_prompt(app: tauri::AppHandle, id: String, folder_id: Option<String>, position: Option<usize>) -> Result<PromptLibrary, String> {
+    prompts::move_to(&app, &id, folder_id, position)
+}
+
+#[tauri::command]
+async fn create_folder(app: tauri::AppHandle, name: String, prompt_ids: Vec<String>) -> Result<Folder, String> {
+    prompts::create_folder(&app, &name, prompt_ids)
+}
+
+#[tauri::command]
+async fn toggle_folder(app: tauri::AppHandle, folder_id: String) -> Result<Folder, String> {
+    prompts::toggle_folder(&app, &folder_id)
+}
+
+#[tauri::command]
+async fn rename_folder(app: tauri::AppHandle, folder_id: String, name: String) -> Result<Folder, String> {
+    prompts::rename_folder(&app, &folder_id, &name)
+}
+
+#[tauri::command]
+async fn delete_folder(app: tauri::AppHandle, folder_id: String) -> Result<(), String> {
+    prompts::delete_folder(&app, &folder_id)
+}
 
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -376,6 +379,75 @@ async fn check_accessibility_permissions() -> Result<bool, String> {
     }
 }
 
+// What prompt delivery can do in the current session.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionCapabilities {
+    // "macos", "x11", "wayland" or "other".
+    session: &'static str,
+    // Seeing which app is frontmost and bringing it back afterwards.
+    focus_tracking: bool,
+    // Typing keystrokes into the target app.
+    typing: bool,
+    // Sending the paste shortcut; clipboard paste works whenever this does.
+    paste: bool,
+}
+
+#[tauri::command]
+async fn get_session_capabilities() -> Result<SessionCapabilities, String> {
+    println!("🔍 Checking session capabilities...");
+    let focus_tracking = window_system::current().is_available();
+    let enigo_ok = match Enigo::new(&Settings::default()) {
+        Ok(_) => true,
+        Err(e) => {
+            println!("❌ Accessibility permissions issue: {}", e);
+            false
+        }
+    };
+
+    #[cfg(target_os = "macos")]
+    let capabilities = SessionCapabilities {
+        session: "macos",
+        focus_tracking,
+        typing: enigo_ok,
+        paste: enigo_ok,
+    };
+
+    // Without a virtual keyboard, enigo's paste shortcut still reaches
+    // XWayland windows.
+    #[cfg(target_os = "linux")]
+    let capabilities = if window_system::is_wayland_session() {
+        let virtual_keyboard = virtual_keyboard::is_available();
+        SessionCapabilities {
+            session: "wayland",
+            focus_tracking,
+            typing: virtual_keyboard,
+            paste: virtual_keyboard || enigo_ok,
+        }
+    } else {
+        SessionCapabilities {
+            session: "x11",
+            focus_tracking,
+            typing: enigo_ok,
+            paste: enigo_ok,
+        }
+    };
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    let capabilities = SessionCapabilities {
+        session: "other",
+        focus_tracking,
+        typing: enigo_ok,
+        paste: enigo_ok,
+    };
+
+    println!(
+        "ℹ️  {} session: focus tracking {}, typing {}, paste {}",
+        capabilities.session, capabilities.focus_tracking, capabilities.typing, capabilities.paste
+    );
+    Ok(capabilities)
+}
+
 #[tauri::command]
 async fn toggle_window_visibility(app: tauri::AppHandle) -> Result<String, String> {
     println!("🔄 Manual window toggle requested");
@@ -411,7 +483,99 @@ async fn toggle_window_visibility(app: tauri::AppHandle) -> Result<String, Strin
 }
 
 #[tauri::command]
-async fn inject_text_at_cursor(_app: tauri::AppHandle, text: String) -> Result<String, String> {
+async fn inject_text_at_cursor(app: tauri::AppHandle, text: String, prompt_id: Option<String>, inputs: Option<HashMap<String, String>>) -> Result<String, String> {
+    // Expand template variables first so a bad placeholder aborts before
+    // anything is typed. A manual target that isn't running aborts too.
+    let settings = with_route(load_settings(&app));
+    let manual_target = manual_target(&settings)?;
+    let target_app = current_target_app(&settings);
+    let mut inputs = inputs.unwrap_or_default();
+
+    // A malformed key macro must abort before anything is typed too.
+    let key_macro = match &prompt_id {
+        Some(id) => match prompts::get(&app, id)?.key_macro {
+            Some(script) => key_macro::parse(&script)?,
+            None => Vec::new(),
+        },
+        None => Vec::new(),
+    };
+
+    // Ask for any `{{input:...}}` values the caller didn't supply. The form
+    // takes focus, so hand it back to the target app whichever way it closes.
+    if template::has_variables(&text) {
+        let missing: Vec<_> = template::input_variables(&template::parse(&text)?)
+            .into_iter()
+            .filter(|(name, _)| !inputs.contains_key(name))
+            .collect();
+        if !missing.is_empty() {
+            let answer = input_form::request(&app, missing).await;
+            if let Err(e) = activate_last_app(app.clone()).await {
+                println!("⚠️ Failed to restore focus after input form: {}", e);
+            }
+            match answer? {
+                Some(values) => inputs.extend(values),
+                None => return Err("Injection cancelled".to_string()),
+            }
+        }
+    }
+
+    let text = template::expand(&app, &text, target_app.as_deref(), &inputs)?;
+
+    if let Some(target) = &manual_target {
+        activate_manual_target(target)?;
+    }
+    // There's no click-to-inject here, so the text goes wherever focus is,
+    // which is still the bar. Hand focus back to the target app first.
+    #[cfg(not(target_os = "macos"))]
+    {
+        if manual_target.is_none() {
+            activate_last_app(app.clone()).await?;
+        }
+    }
+    let result = inject_text(&app, &text, &settings, target_app.as_deref(), &key_macro)?;
+
+    // Record the use so frequently injected prompts rank higher. A failure
+    // here must not turn a successful injection into an error.
+    if let Some(prompt_id) = prompt_id {
+        if let Err(e) = usage::record(&app, &prompt_id, target_app) {
+            println!("⚠️ Failed to record prompt usage: {}", e);
+        }
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+async fn get_pending_inputs() -> Result<Option<InputRequest>, String> {
+    Ok(input_form::pending())
+}
+
+#[tauri::command]
+async fn submit_inputs(app: tauri::AppHandle, id: String, values: HashMap<String, String>) -> Result<(), String> {
+    input_form::submit(&app, &id, values)
+}
+
+#[tauri::command]
+async fn cancel_inputs(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    input_form::cancel(&app, &id)
+}
+
+// Expand a prompt's template variables without injecting it, so the editor can
+// preview the result and surface template errors.
+#[tauri::command]
+async fn expand_template(app: tauri::AppHandle, text: String, inputs: Option<HashMap<String, String>>) -> Result<String, String> {
+    let target_app = current_target_app(&load_settings(&app));
+    template::expand(&app, &text, target_app.as_deref(), &inputs.unwrap_or_default())
+}
+
+// Prompts ranked for `target_app`, by default the app prompts currently go to.
+#[tauri::command]
+async fn get_ranked_prompts(app: tauri::AppHandle, target_app: Option<String>) -> Result<Vec<RankedPrompt>, String> {
+    let target_app = target_app.or_else(|| current_target_app(&load_settings(&app)));
+    usage::ranked_prompts(&app, target_app.as_deref())
+}
+
+fn inject_text(app: &AppHandle, text: &str, settings: &AppSettings, target_app: Option<&str>, key_macro: &[Action]) -> Result<String, String> {
     println!("🚀 Starting click-to-inject mode...");
     println!("📝 Text to inject: '{}'", text);
     println!("📏 Text length: {} characters", text.len());
@@ -492,17 +656,19 @@ async fn inject_text_at_cursor(_app: tauri::AppHandle, text: String) -> Result<S
                 
                 // Small delay to ensure the click is fully processed
                 std::thread::sleep(std::time::Duration::from_millis(100));
+
+                // The click may have landed on the bar or the desktop
+                focus::check_frontmost(window_system::current())?;
                 
                 // Now inject the text at wherever the cursor currently is (not at click location)
                 match Enigo::new(&Settings::default()) {
                     Ok(mut enigo) => {
-                        match enigo.text(&text) {
+                        match injection::deliver(app, &mut enigo, text, settings, target_app, key_macro) {
                             Ok(_) => {
                                 println!("✅ Text injected successfully at cursor position");
                                 Ok(format!("Text injected: {}", text))
                             }
-                            Err(e) => {
-                                let error_msg = format!("❌ Failed to inject text: {}", e);
+                            Err(error_msg) => {
                                 println!("{}", error_msg);
                                 Err(error_msg)
                             }
@@ -527,17 +693,30 @@ async fn inject_text_at_cursor(_app: tauri::AppHandle, text: String) -> Result<S
     {
         // For non-macOS platforms, we'll implement a simpler approach
         println!("🔄 Click-to-inject not yet implemented for this platform");
+
+        #[cfg(target_os = "linux")]
+        if window_system::is_wayland_session() {
+            return match injection::deliver_wayland(app, text, settings, target_app, key_macro) {
+                Ok(_) => {
+                    println!("✅ Text injected at current cursor position");
+                    Ok(format!("Text injected: {}", text))
+                }
+                Err(error_msg) => {
+                    println!("{}", error_msg);
+                    Err(error_msg)
+                }
+            };
+        }
         
         // Fallback: inject text immediately at current cursor position
         match Enigo::new(&Settings::default()) {
             Ok(mut enigo) => {
-                match enigo.text(&text) {
+                match injection::deliver(app, &mut enigo, text, settings, target_app, key_macro) {
                     Ok(_) => {
                         println!("✅ Text injected at current cursor position");
                         Ok(format!("Text injected: {}", text))
                     }
-                    Err(e) => {
-                        let error_msg = format!("❌ Failed to inject text: {}", e);
+                    Err(error_msg) => {
                         println!("{}", error_msg);
                         Err(error_msg)
                     }
@@ -553,270 +732,6 @@ async fn inject_text_at_cursor(_app: tauri::AppHandle, text: String) -> Result<S
 }
 
 
-// Function to detect target app automatically
-#[cfg(target_os = "macos")]
-fn detect_target_app() -> Option<String> {
-    // First, let's get comprehensive app information to debug why Qoder isn't appearing
-    println!("🔍 === DEBUGGING APP DETECTION ===");
-    
-    // Try multiple approaches to find all running apps
-    let all_processes_output = Command::new("osascript")
-        .arg("-e")
-        .arg("tell application \"System Events\" to get name of every application process")
-        .output();
-        
-    if let Ok(output) = all_processes_output {
-        if output.status.success() {
-            let all_processes = String::from_utf8_lossy(&output.stdout);
-            println!("🔍 ALL running processes: {}", all_processes.trim());
-        }
-    }
-    
-    // Try using `ps` command to get all processes (more comprehensive)
-    let ps_output = Command::new("ps")
-        .arg("-axo")
-        .arg("comm")
-        .output();
-        
-    if let Ok(output) = ps_output {
-        if output.status.success() {
-            let ps_processes = String::from_utf8_lossy(&output.stdout);
-            let ps_lines: Vec<&str> = ps_processes.lines().collect();
-            let qoder_processes: Vec<&str> = ps_lines.iter()
-                .filter(|line| line.to_lowercase().contains("qoder"))
-                .cloned()
-                .collect();
-                
-            if !qoder_processes.is_empty() {
-                println!("🔍 FOUND Qoder processes via ps: {:?}", qoder_processes);
-            } else {
-                println!("🔍 NO Qoder processes found via ps");
-            }
-        }
-    }
-    
-    // Try using `pgrep` to find Qoder specifically
-    let pgrep_output = Command::new("pgrep")
-        .arg("-f")
-        .arg("-i")
-        .arg("qoder")
-        .output();
-        
-    if let Ok(output) = pgrep_output {
-        if output.status.success() {
-            let pids = String::from_utf8_lossy(&output.stdout);
-            if !pids.trim().is_empty() {
-                println!("🔍 FOUND Qoder PIDs via pgrep: {}", pids.trim());
-                
-                // Try to get more info about these processes
-                for pid in pids.trim().lines() {
-                    let ps_info_output = Command::new("ps")
-                        .arg("-p")
-                        .arg(pid)
-                        .arg("-o")
-                        .arg("comm,args")
-                        .output();
-                        
-                    if let Ok(info_output) = ps_info_output {
-                        if info_output.status.success() {
-                            let info = String::from_utf8_lossy(&info_output.stdout);
-                            println!("🔍 Qoder process {} info: {}", pid, info.trim());
-                        }
-                    }
-                }
-            } else {
-                println!("🔍 NO Qoder PIDs found via pgrep");
-            }
-        }
-    }
-    
-    // Try alternative AppleScript queries for hidden/background apps
-    let all_apps_output = Command::new("osascript")
-        .arg("-e")
-        .arg("tell application \"System Events\" to get name of every application process whose background only is false")
-        .output();
-        
-    if let Ok(output) = all_apps_output {
-        if output.status.success() {
-            let all_apps = String::from_utf8_lossy(&output.stdout);
-            println!("🔍 ALL non-background apps: {}", all_apps.trim());
-        }
-    }
-    
-    // Get visible processes
-    let visible_output = Command::new("osascript")
-        .arg("-e")
-        .arg("tell application \"System Events\" to get name of every application process whose visible is true")
-        .output();
-        
-    if let Ok(output) = visible_output {
-        if output.status.success() {
-            let visible_processes = String::from_utf8_lossy(&output.stdout);
-            println!("🔍 VISIBLE processes: {}", visible_processes.trim());
-        }
-    }
-    
-    // Get frontmost processes in order
-    let frontmost_list_output = Command::new("osascript")
-        .arg("-e")
-        .arg("tell application \"System Events\" to get name of every application process whose frontmost is true")
-        .output();
-        
-    if let Ok(output) = frontmost_list_output {
-        if output.status.success() {
-            let frontmost_list = String::from_utf8_lossy(&output.stdout);
-            println!("🔍 FRONTMOST processes (in order): {}", frontmost_list.trim());
-        }
-    }
-    
-    println!("🔍 === END DEBUGGING ===");
-    
-    // Now try our normal detection logic
-    let frontmost_output = Command::new("osascript")
-        .arg("-e")
-        .arg("tell application \"System Events\" to get name of first application process whose frontmost is true")
-        .output();
-        
-    let mut target_app: Option<String> = None;
-    
-    // Check the frontmost app first
-    if let Ok(output) = frontmost_output {
-        if output.status.success() {
-            let frontmost = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            println!("🎯 Frontmost app: {}", frontmost);
-            
-            // If frontmost is not our app and is suitable, use it
-            if frontmost != "Prompt Buddy" && frontmost != "prompt-buddy" && !is_problematic_app(&frontmost) && is_valid_app_name(&frontmost) {
-                target_app = Some(frontmost);
-                println!("✅ Using frontmost app as target");
-            }
-        }
-    }
-    
-    // If frontmost app is not suitable, try to get all frontmost apps and pick the second one
-    if target_app.is_none() {
-        println!("🔍 Frontmost not suitable, trying to get second frontmost...");
-        
-        // Get all frontmost processes (should be ordered by most recent)
-        let all_frontmost_output = Command::new("osascript")
-            .arg("-e")
-            .arg("tell application \"System Events\" to get name of every application process whose frontmost is true")
-            .output();
-            
-        if let Ok(output) = all_frontmost_output {
-            if output.status.success() {
-                let processes_str = String::from_utf8_lossy(&output.stdout);
-                let processes: Vec<&str> = processes_str.split(", ").map(|s| s.trim()).collect();
-                
-                println!("🎯 All frontmost apps in order: {:?}", processes);
-                
-                // Skip the first one (our app) and find the first suitable one
-                for (i, process) in processes.iter().enumerate() {
-                    println!("🔍 Checking process #{}: {}", i, process);
-                    
-                    if i > 0 && // Skip the first one
-                       *process != "Prompt Buddy" && 
-                       *process != "prompt-buddy" && 
-                       !is_problematic_app(process) && 
-                       is_valid_app_name(process) {
-                        println!("✅ Found suitable target app from frontmost list: {}", process);
-                        target_app = Some(process.to_string());
-                        break;
-                    } else {
-                        println!("🚫 Skipping unsuitable app: {}", process);
-                    }
-                }
-            }
-        }
-    }
-    
-    // If we still don't have a target, check all visible apps
-    if target_app.is_none() {
-        println!("🔍 No suitable frontmost apps found, checking all visible apps...");
-        
-        // Get apps that are visible
-        let space_apps_output = Command::new("osascript")
-            .arg("-e")
-            .arg("tell application \"System Events\" to get name of every application process whose visible is true")
-            .output();
-        
-        if let Ok(output) = space_apps_output {
-            if output.status.success() {
-                let processes_str = String::from_utf8_lossy(&output.stdout);
-                let processes: Vec<&str> = processes_str.split(", ").map(|s| s.trim()).collect();
-                
-                println!("🎯 All visible apps: {:?}", processes);
-                
-                // Look specifically for Qoder first
-                for process in &processes {
-                    if process.contains("Qoder") || process.contains("qoder") {
-                        println!("🎯 Found Qoder in visible apps: {}", process);
-                        target_app = Some(process.to_string());
-                        break;
-                    }
-                }
-                
-                // If no Qoder found, reverse the order to prioritize newer apps (like Electron-based ones)
-                if target_app.is_none() {
-                    let mut reversed_processes = processes.clone();
-                    reversed_processes.reverse();
-                    println!("🔄 Reversed process order (newer apps first): {:?}", reversed_processes);
-                    
-                    for process in reversed_processes {
-                        if process != "Prompt Buddy" && process != "prompt-buddy" && !is_problematic_app(process) && is_valid_app_name(process) {
-                            println!("🎯 Found suitable target app from reversed visible apps: {}", process);
-                            target_app = Some(process.to_string());
-                            break;
-                        } else {
-                            println!("🚫 Skipping unsuitable app: {}", process);
-                        }
-                    }
-                }
-            }
-        }
-    }
-    
-    // Handle Electron resolution
-    if let Some(app_name) = &target_app {
-        if app_name == "Electron" {
-            println!("🎯 Detected Electron - attempting to resolve to actual app...");
-            
-            // Try to get the actual app name from the Electron process
-            let electron_app = get_electron_app_name();
-            if let Some(resolved_name) = electron_app {
-                println!("🎯 Resolved Electron to: {}", resolved_name);
-                return Some(resolved_name);
-            } else {
-                println!("⚠️ Could not resolve Electron app, trying direct Qoder activation...");
-                
-                // Try direct Qoder activation as fallback
-                let qoder_variants = ["Qoder", "Qoder IDE", "qoder", "Qoder.app"];
-                for variant in qoder_variants.iter() {
-                    let test_cmd = format!("tell application \"{}\" to get name", variant);
-                    let test_result = Command::new("osascript")
-                        .arg("-e")
-                        .arg(test_cmd)
-                        .output();
-                        
-                    if let Ok(output) = test_result {
-                        if output.status.success() {
-                            println!("✅ Found working Qoder variant: {}", variant);
-                            return Some(variant.to_string());
-                        }
-                    }
-                }
-            }
-        }
-    }
-    
-    target_app
-}
-
-#[cfg(not(target_os = "macos"))]
-fn detect_target_app() -> Option<String> {
-    None
-}
-
 #[tauri::command]
 async fn show_popup(app: AppHandle, x: f64, y: f64, prompt: String, index: usize) {
     println!("🎯 Showing popup for index {}", index);
@@ -882,55 +797,16 @@ async fn capture_frontmost_app() -> Result<(), String> {
 
 // Command that re-activates the application we previously captured with
 // `remember_current_app()`.  The frontend can call this right after a pill
-// click so macOS focus is switched back before we start typing.
+// click so macOS focus is switched back before we start typing. In manual
+// target mode the configured app is activated instead.
 #[tauri::command]
-async fn activate_last_app() -> Result<(), String> {
-    #[cfg(target_os = "macos")]
-    {
-        // Sleep briefly to let any previously launched apps settle
-        std::thread::sleep(std::time::Duration::from_millis(100));
-        
-        if let Some(app_name) = LAST_APP_NAME.lock().unwrap().clone() {
-            println!("🔄 Tauri cmd: activating last app = {}", app_name);
-            
-            // Check if the last app is problematic
-            if is_problematic_app(&app_name) {
-                println!("⚠️ Detected problematic app as last app: {}", app_name);
-                
-                // Try to use the app before the current one
-                if let Some(previous_app) = PREVIOUS_APP_NAME.lock().unwrap().clone() {
-                    println!("🔄 Falling back to previous app: {}", previous_app);
-                    
-                    // Check if previous app is also problematic
-                    if !is_problematic_app(&previous_app) && activate_app(&previous_app) {
-                        return Ok(());
-                    } else {
-                        println!("⚠️ Failed to activate previous app or it's problematic: {}", previous_app);
-                        // Don't fall through to try the problematic app
-                        return Ok(());
-                    }
-                } else {
-                    println!("⚠️ No previous app available, and current app is problematic. Will not activate.");
-                    return Ok(());
-                }
-            }
-            
-            // Try to activate the last app
-            if activate_app(&app_name) {
-                return Ok(());
-            } else {
-                return Err(format!("Failed to activate {}", app_name));
-            }
-        }
-        println!("ℹ️  No last app recorded - nothing to activate");
-        Ok(())
+async fn activate_last_app(app: AppHandle) -> Result<(), String> {
+    if let Some(target) = manual_target(&load_settings(&app))? {
+        return activate_manual_target(&target);
     }
 
-    #[cfg(not(target_os = "macos"))]
-    {
-        println!("🔄 activate_last_app called on non-macOS platform – noop");
-        Ok(())
-    }
+    let recent: Vec<_> = focus_history::recent().into_iter().map(|entry| entry.window).collect();
+    focus::restore(window_system::current(), &recent, &focus::SystemClock)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -941,121 +817,31 @@ pub fn run() {
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
-        .invoke_handler(tauri::generate_handler![greet, inject_text_at_cursor, check_accessibility_permissions, toggle_window_visibility, show_popup, hide_popup, capture_frontmost_app, activate_last_app, get_settings, save_settings])
+        .invoke_handler(tauri::generate_handler![greet, inject_text_at_cursor, expand_template, get_pending_inputs, submit_inputs, cancel_inputs, check_accessibility_permissions, get_session_capabilities, toggle_window_visibility, show_popup, hide_popup, capture_frontmost_app, activate_last_app, get_settings, save_settings, get_current_route, list_recent_targets, activate_target, test_target_app, get_shortcut_status, record_shortcut, submit_recorded_keys, cancel_shortcut_recording, get_migration_status, get_ranked_prompts, list_prompts, search_prompts, create_prompt, update_prompt, delete_prompt, move_prompt, create_folder, toggle_folder, rename_folder, delete_folder])
         .setup(|app| {
+            // Upgrade settings.json and prompts.json before anything reads them
+            migrations::run_all(&app.handle());
+
             println!("🔧 Setting up global shortcuts with handlers...");
             
             // Load settings to get the configured shortcut
             let settings = load_settings(&app.handle());
+            routing::set_rules(settings.routing_rules.clone());
+            ignore_list::set(settings.ignore_list.clone());
             
-            // Register main shortcut with handler in one step
-            println!("🎯 Registering main toggle shortcut: {}...", settings.toggle_shortcut);
-            let shortcut_string = settings.toggle_shortcut.clone();
-            let main_shortcut: Shortcut = settings.toggle_shortcut.parse().map_err(|e| format!("Failed to parse main shortcut '{}': {}", settings.toggle_shortcut, e))?;
-            match app.handle().global_shortcut().on_shortcut(main_shortcut, move |_app, _shortcut, _state| {
-                // Only act on key *press* events so the shortcut truly toggles.
-                if _state.state() == ShortcutState::Pressed {
-                    println!("🎯 Global shortcut ({}) pressed!", shortcut_string);
-
-                    if let Some(window) = _app.get_webview_window("main") {
-                        println!("✅ Found main window");
-                        match window.is_visible() {
-                            Ok(is_visible) => {
-                                println!("👁️  Current window visibility: {}", is_visible);
-                                if is_visible {
-                                    println!("🫥 Hiding prompt picker bar");
-                                    if let Err(e) = window.hide() {
-                                        println!("❌ Failed to hide window: {}", e);
-                                    }
-                                } else {
-                                    // Before showing the window we record the app
-                                    // that is currently frontmost so we can switch
-                                    // back to it later when the user selects a prompt.
-                                    remember_current_app();
-
-                                    println!("👁️  Showing prompt picker bar");
-                                    if let Err(e) = window.show() {
-                                        println!("❌ Failed to show window: {}", e);
-                                    } else {
-                                        println!("✅ Window shown successfully");
-                                        let _ = window.set_focus();
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                println!("❌ Failed to get window visibility: {}", e);
-                                // Capture frontmost app before stealing focus
-                                remember_current_app();
-
-                                println!("🔄 Attempting to show window anyway...");
-                                if let Err(e) = window.show() {
-                                    println!("❌ Failed to show window: {}", e);
-                                } else {
-                                    let _ = window.set_focus();
-                                }
-                            }
-                        }
-                    } else {
-                        println!("❌ Could not find main window");
-                    }
-                }
-            }) {
-                Ok(_) => {
-                    println!("✅ Main shortcut ({}) registered successfully!", settings.toggle_shortcut);
-                }
-                Err(e) => {
-                    println!("❌ Failed to register main shortcut: {}", e);
-                    println!("⚠️  You can still use the app manually, but {} won't work", settings.toggle_shortcut);
-                }
-            }
+            shortcuts::register_toggle(&app.handle(), &settings.toggle_shortcut);
+            chords::replace_leader(&app.handle(), &settings.leader_shortcut);
             
-            // Register prompt injection shortcuts with handlers
+            // Register the shortcuts stored with each prompt
             println!("🎯 Registering prompt injection shortcuts...");
-            let mut successful_shortcuts = 0;
-            for i in 1..=9 {
-                let shortcut_str = format!("cmd+alt+{}", i);
-                let prompt_index = i - 1; // Convert to 0-based index
-                
-                match shortcut_str.parse::<Shortcut>() {
-                    Ok(shortcut) => {
-                        match app.handle().global_shortcut().on_shortcut(shortcut, move |app, _shortcut, _state| {
-                            if _state.state() == ShortcutState::Pressed {
-                                println!("🚀 Prompt shortcut triggered: Cmd+Alt+{}", i);
-                                // Emit event to frontend to trigger injection
-                                if let Some(window) = app.get_webview_window("main") {
-                                    if let Err(e) = window.emit("inject-prompt", prompt_index) {
-                                        println!("❌ Failed to emit inject-prompt event: {}", e);
-                                    } else {
-                                        println!("✅ Emitted inject-prompt event for index: {}", prompt_index);
-                                    }
-                                }
-                            }
-                        }) {
-                            Ok(_) => {
-                                println!("✅ Registered: {}", shortcut_str);
-                                successful_shortcuts += 1;
-                            }
-                            Err(e) => {
-                                println!("❌ Failed to register {}: {} (probably conflicts with another app)", shortcut_str, e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        println!("❌ Failed to parse shortcut {}: {}", shortcut_str, e);
-                    }
-                }
-            }
-            
-            if successful_shortcuts == 0 {
-                println!("⚠️  No prompt shortcuts could be registered - they may conflict with existing shortcuts");
-                println!("💡 You can still use the app's interface to select and inject prompts");
-            } else {
-                println!("🎯 Successfully registered {} out of 9 prompt shortcuts", successful_shortcuts);
+            match prompts::list(&app.handle()) {
+                Ok(library) => shortcuts::sync_prompts(&app.handle(), &library),
+                Err(e) => println!("❌ Failed to load prompts for shortcut registration: {}", e),
             }
             
             println!("🎯 Prompt Picker initialized successfully!");
             println!("📋 Use Alt+Space to show/hide the prompt picker bar");
-            println!("🎯 Use Cmd+Alt+1-9 to inject prompts");
+            println!("🎯 Use each prompt's shortcut to inject it");
             println!("⚠️  Note: On macOS, you may need to grant accessibility permissions");
             
             // Show window on first launch for better user experience
@@ -1076,25 +862,7 @@ pub fn run() {
 }
 
 // Helper that records the currently frontmost application so we can restore
-// focus later. Only meaningful on macOS.
+// focus later.
 fn remember_current_app() {
-    #[cfg(target_os = "macos")]
-    {
-        if let Some(name) = get_frontmost_app() {
-            println!("💾 Remembering current frontmost app: {}", name);
-            
-            // Get a lock on both mutexes
-            let mut last_app_lock = LAST_APP_NAME.lock().unwrap();
-            let mut prev_app_lock = PREVIOUS_APP_NAME.lock().unwrap();
-            
-            // If there is a current last app, store it as the previous app
-            if let Some(ref last_app) = *last_app_lock {
-                println!("💾 Updating previous app to: {}", last_app);
-                *prev_app_lock = Some(last_app.clone());
-            }
-            
-            // Set the new last app
-            *last_app_lock = Some(name);
-        }
-    }
+    focus_history::capture(window_system::current());
 }
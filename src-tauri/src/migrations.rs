@@ -0,0 +1,502 @@
+// Versioned schema and migrations for the JSON store files.
+//
+// Each store file carries a top-level `schemaVersion` key. On startup every
+// file is upgraded one step at a time up to the version this build expects.
+// The original file is copied to `<file>.v<N>.bak` before anything is changed,
+// and a failing migration leaves the store untouched and is reported to the UI
+// through `get_migration_status` / the `migration-failed` event.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_store::StoreExt;
+
+pub const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+
+// A migration upgrades a whole store document from version N to N + 1.
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+pub struct StoreSchema {
+    pub file: &'static str,
+    // `migrations[i]` upgrades a document from version `i` to `i + 1`, so the
+    // current version is simply the number of migrations.
+    pub migrations: &'static [Migration],
+}
+
+impl StoreSchema {
+    pub fn current_version(&self) -> u64 {
+        self.migrations.len() as u64
+    }
+}
+
+pub const SETTINGS_SCHEMA: StoreSchema = StoreSchema {
+    file: "settings.json",
+    migrations: &[settings_v0_to_v1, settings_v1_to_v2],
+};
+
+pub const PROMPTS_SCHEMA: StoreSchema = StoreSchema {
+    file: crate::prompts::PROMPTS_STORE,
+    migrations: &[prompts_v0_to_v1, prompts_v1_to_v2, prompts_v2_to_v3],
+};
+
+pub const USAGE_SCHEMA: StoreSchema = StoreSchema {
+    file: crate::usage::USAGE_STORE,
+    migrations: &[],
+};
+
+pub const INPUTS_SCHEMA: StoreSchema = StoreSchema {
+    file: crate::input_form::INPUTS_STORE,
+    migrations: &[],
+};
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    pub file: String,
+    pub from_version: u64,
+    pub to_version: u64,
+    pub status: String, // "current", "created", "migrated" or "failed"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_path: Option<String>,
+}
+
+static MIGRATION_REPORTS: Lazy<Mutex<Vec<MigrationReport>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+pub fn reports() -> Vec<MigrationReport> {
+    MIGRATION_REPORTS.lock().unwrap().clone()
+}
+
+// Record that `file` couldn't be read after migrating, e.g. settings that
+// don't match `AppSettings`, so the UI can say so instead of the defaults
+// silently taking over. Reported once per distinct error.
+pub fn report_unreadable(app: &AppHandle, file: &str, error: &str) {
+    let report = {
+        let mut reports = MIGRATION_REPORTS.lock().unwrap();
+        if reports.iter().any(|r| r.file == file && r.error.as_deref() == Some(error)) {
+            return;
+        }
+        let version = reports.iter().find(|r| r.file == file).map(|r| r.to_version).unwrap_or(0);
+        let report = MigrationReport {
+            file: file.to_string(),
+            from_version: version,
+            to_version: version,
+            status: "failed".to_string(),
+            error: Some(error.to_string()),
+            backup_path: None,
+        };
+        reports.retain(|r| r.file != file);
+        reports.push(report.clone());
+        report
+    };
+    if let Err(e) = app.emit("migration-failed", report) {
+        println!("❌ Failed to emit migration-failed event: {}", e);
+    }
+}
+
+// Upgrade every store file this build knows about. Called from `setup` before
+// anything reads settings or prompts.
+pub fn run_all(app: &AppHandle) {
+    let mut reports = Vec::new();
+    for schema in [&SETTINGS_SCHEMA, &PROMPTS_SCHEMA, &USAGE_SCHEMA, &INPUTS_SCHEMA] {
+        let report = migrate_store(app, schema);
+        match report.status.as_str() {
+            "failed" => {
+                println!("❌ Migration of {} failed: {}", report.file, report.error.as_deref().unwrap_or("unknown error"));
+                if let Err(e) = app.emit("migration-failed", report.clone()) {
+                    println!("❌ Failed to emit migration-failed event: {}", e);
+                }
+            }
+            "migrated" => println!("✅ Migrated {} from v{} to v{}", report.file, report.from_version, report.to_version),
+            _ => println!("✅ {} is at schema v{}", report.file, report.to_version),
+        }
+        reports.push(report);
+    }
+    *MIGRATION_REPORTS.lock().unwrap() = reports;
+}
+
+fn migrate_store(app: &AppHandle, schema: &StoreSchema) -> MigrationReport {
+    let target = schema.current_version();
+    let mut report = MigrationReport {
+        file: schema.file.to_string(),
+        from_version: 0,
+        to_version: target,
+        status: "current".to_string(),
+        error: None,
+        backup_path: None,
+    };
+
+    let fail = |mut report: MigrationReport, error: String| {
+        report.status = "failed".to_string();
+        report.error = Some(error);
+        report
+    };
+
+    let store = match app.store(schema.file) {
+        Ok(store) => store,
+        Err(e) => return fail(report, format!("Failed to load store: {}", e)),
+    };
+
+    let mut document: Map<String, Value> = store.entries().into_iter().collect();
+
+    // A brand new install has nothing to migrate; just stamp the version.
+    if document.is_empty() {
+        report.from_version = target;
+        report.status = "created".to_string();
+        store.set(SCHEMA_VERSION_KEY, target);
+        if let Err(e) = store.save() {
+            return fail(report, format!("Failed to save store: {}", e));
+        }
+        return report;
+    }
+
+    let from = match document.get(SCHEMA_VERSION_KEY) {
+        None => 0,
+        Some(value) => match value.as_u64() {
+            Some(version) => version,
+            None => return fail(report, format!("Invalid {}: {}", SCHEMA_VERSION_KEY, value)),
+        },
+    };
+    report.from_version = from;
+
+    if from == target {
+        return report;
+    }
+    if from > target {
+        return fail(
+            report,
+            format!("{} was written by a newer version of Prompt Buddy (schema v{}, this build supports v{})", schema.file, from, target),
+        );
+    }
+
+    match backup_store_file(app, schema.file, from) {
+        Ok(path) => report.backup_path = path,
+        Err(e) => return fail(report, e),
+    }
+
+    if let Err(e) = migrate_document(&mut document, schema) {
+        return fail(report, e);
+    }
+
+    store.clear();
+    for (key, value) in document {
+        store.set(key, value);
+    }
+    if let Err(e) = store.save() {
+        return fail(report, format!("Failed to save migrated store: {}", e));
+    }
+
+    report.status = "migrated".to_string();
+    report
+}
+
+// Apply every pending migration to `document`. The document is only modified
+// if all steps succeed.
+pub fn migrate_document(document: &mut Map<String, Value>, schema: &StoreSchema) -> Result<(), String> {
+    let from = document
+        .get(SCHEMA_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+
+    let mut migrated = document.clone();
+    for version in from..schema.current_version() {
+        println!("🔧 Migrating {} from v{} to v{}", schema.file, version, version + 1);
+        let migration = schema.migrations[version as usize];
+        migration(&mut migrated)
+            .map_err(|e| format!("Migration of {} from v{} to v{} failed: {}", schema.file, version, version + 1, e))?;
+        migrated.insert(SCHEMA_VERSION_KEY.to_string(), Value::from(version + 1));
+    }
+
+    *document = migrated;
+    Ok(())
+}
+
+// Copy the on-disk store file next to itself before migrating it. Returns
+// `None` if there is no file on disk yet.
+fn backup_store_file(app: &AppHandle, file: &str, version: u64) -> Result<Option<String>, String> {
+    let path = tauri_plugin_store::resolve_store_path(app, file)
+        .map_err(|e| format!("Failed to resolve path of {}: {}", file, e))?;
+    backup_file(&path, file, version)
+}
+
+fn backup_file(path: &Path, file: &str, version: u64) -> Result<Option<String>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let backup_path = path.with_file_name(format!("{}.v{}.bak", file, version));
+    std::fs::copy(path, &backup_path)
+        .map_err(|e| format!("Failed to back up {} to {}: {}", file, backup_path.display(), e))?;
+    println!("💾 Backed up {} to {}", file, backup_path.display());
+
+    Ok(Some(backup_path.display().to_string()))
+}
+
+// v0 → v1: settings were written either by `save_settings` (snake_case) or by
+// the SettingsPage store fallback (camelCase). Normalise them to camelCase,
+// which is what the UI sends and `AppSettings` now expects.
+fn settings_v0_to_v1(document: &mut Map<String, Value>) -> Result<(), String> {
+    let settings = match document.get_mut("settings") {
+        None => return Ok(()),
+        Some(Value::Object(settings)) => settings,
+        Some(other) => return Err(format!("expected \"settings\" to be an object, found {}", other)),
+    };
+
+    for (old, new) in [
+        ("toggle_shortcut", "toggleShortcut"),
+        ("target_mode", "targetMode"),
+        ("target_app_name", "targetAppName"),
+    ] {
+        if let Some(value) = settings.remove(old) {
+            // A camelCase value written later by the fallback wins.
+            settings.entry(new.to_string()).or_insert(value);
+        }
+    }
+
+    Ok(())
+}
+
+// v1 → v2: `save_settings` now holds the paste threshold and clipboard restore
+// delay to bounds. Pull saved values into them, or no other setting could be
+// saved until they were fixed by hand.
+fn settings_v1_to_v2(document: &mut Map<String, Value>) -> Result<(), String> {
+    use crate::injection::{MAX_PASTE_THRESHOLD, MAX_RESTORE_DELAY_MS, MIN_RESTORE_DELAY_MS};
+
+    let settings = match document.get_mut("settings") {
+        None => return Ok(()),
+        Some(Value::Object(settings)) => settings,
+        Some(other) => return Err(format!("expected \"settings\" to be an object, found {}", other)),
+    };
+
+    if let Some(threshold) = settings.get("pasteThreshold").and_then(Value::as_u64) {
+        settings.insert("pasteThreshold".to_string(), Value::from(threshold.min(MAX_PASTE_THRESHOLD as u64)));
+    }
+    if let Some(delay) = settings.get("clipboardRestoreDelayMs").and_then(Value::as_u64) {
+        let delay = delay.clamp(MIN_RESTORE_DELAY_MS, MAX_RESTORE_DELAY_MS);
+        settings.insert("clipboardRestoreDelayMs".to_string(), Value::from(delay));
+    }
+
+    Ok(())
+}
+
+// v0 → v1: drop the `_trigger` polling key and make sure every prompt has a
+// string id and a colour.
+fn prompts_v0_to_v1(document: &mut Map<String, Value>) -> Result<(), String> {
+    document.remove("_trigger");
+
+    match document.get_mut("prompts") {
+        None => {}
+        Some(Value::Array(prompts)) => {
+            for prompt in prompts.iter_mut() {
+                normalize_prompt_v1(prompt)?;
+            }
+        }
+        Some(other) => return Err(format!("expected \"prompts\" to be an array, found {}", other)),
+    }
+
+    let folders = match document.get_mut("folders") {
+        None => return Ok(()),
+        Some(Value::Array(folders)) => folders,
+        Some(other) => return Err(format!("expected \"folders\" to be an array, found {}", other)),
+    };
+    for folder in folders.iter_mut() {
+        if let Some(Value::Array(prompts)) = folder.get_mut("prompts") {
+            for prompt in prompts.iter_mut() {
+                normalize_prompt_v1(prompt)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn normalize_prompt_v1(prompt: &mut Value) -> Result<(), String> {
+    let prompt = match prompt {
+        Value::Object(prompt) => prompt,
+        other => return Err(format!("expected prompt to be an object, found {}", other)),
+    };
+
+    if let Some(Value::Number(id)) = prompt.get("id") {
+        let id = id.to_string();
+        prompt.insert("id".to_string(), Value::from(id));
+    }
+    if !prompt.contains_key("color") {
+        prompt.insert("color".to_string(), Value::from("from-blue-500 to-cyan-500"));
+    }
+
+    Ok(())
+}
+
+// v1 → v2: prompt shortcuts are stored with each prompt instead of being the
+// hardcoded Cmd+Alt+1-9 positions, so hand those out to the first nine
+// prompts to keep existing muscle memory working.
+fn prompts_v1_to_v2(document: &mut Map<String, Value>) -> Result<(), String> {
+    let prompts = match document.get_mut("prompts") {
+        None => return Ok(()),
+        Some(Value::Array(prompts)) => prompts,
+        Some(other) => return Err(format!("expected \"prompts\" to be an array, found {}", other)),
+    };
+
+    for (i, prompt) in prompts.iter_mut().take(9).enumerate() {
+        let prompt = match prompt {
+            Value::Object(prompt) => prompt,
+            other => return Err(format!("expected prompt to be an object, found {}", other)),
+        };
+        prompt
+            .entry("shortcut".to_string())
+            .or_insert_with(|| Value::from(format!("cmd+alt+{}", i + 1)));
+    }
+
+    Ok(())
+}
+
+// v2 → v3: `{{` now starts a template variable. Prompts that don't parse as
+// templates were written before that and mean their braces literally, so
+// escape them rather than have every injection fail on an unknown variable.
+fn prompts_v2_to_v3(document: &mut Map<String, Value>) -> Result<(), String> {
+    match document.get_mut("prompts") {
+        None => {}
+        Some(Value::Array(prompts)) => escape_literal_braces(prompts)?,
+        Some(other) => return Err(format!("expected \"prompts\" to be an array, found {}", other)),
+    }
+
+    // Folders hold copies of their prompts, which must match.
+    if let Some(Value::Array(folders)) = document.get_mut("folders") {
+        for folder in folders.iter_mut() {
+            if let Some(Value::Array(prompts)) = folder.get_mut("prompts") {
+                escape_literal_braces(prompts)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn escape_literal_braces(prompts: &mut [Value]) -> Result<(), String> {
+    for prompt in prompts.iter_mut() {
+        let prompt = match prompt {
+            Value::Object(prompt) => prompt,
+            other => return Err(format!("expected prompt to be an object, found {}", other)),
+        };
+        if let Some(Value::String(content)) = prompt.get_mut("content") {
+            if crate::template::parse(content).is_err() {
+                *content = crate::template::escape(content);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn document(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            other => panic!("not an object: {}", other),
+        }
+    }
+
+    #[test]
+    fn settings_v0_keys_become_camel_case() {
+        let mut settings = document(json!({
+            "settings": {
+                "toggle_shortcut": "alt+space",
+                "target_mode": "manual",
+                "target_app_name": "Cursor",
+                "targetMode": "auto",
+            }
+        }));
+        migrate_document(&mut settings, &SETTINGS_SCHEMA).unwrap();
+        assert_eq!(
+            Value::Object(settings),
+            json!({
+                "schemaVersion": 2,
+                "settings": {
+                    "toggleShortcut": "alt+space",
+                    "targetMode": "auto",
+                    "targetAppName": "Cursor",
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn settings_v1_paste_values_are_pulled_into_bounds() {
+        let mut settings = document(json!({
+            "schemaVersion": 1,
+            "settings": { "pasteThreshold": 5_000_000, "clipboardRestoreDelayMs": 0 }
+        }));
+        migrate_document(&mut settings, &SETTINGS_SCHEMA).unwrap();
+        assert_eq!(settings["settings"], json!({ "pasteThreshold": 100_000, "clipboardRestoreDelayMs": 100 }));
+
+        let mut settings = document(json!({ "schemaVersion": 1, "settings": { "pasteThreshold": 200 } }));
+        migrate_document(&mut settings, &SETTINGS_SCHEMA).unwrap();
+        assert_eq!(settings["settings"], json!({ "pasteThreshold": 200 }));
+    }
+
+    #[test]
+    fn prompts_v1_get_the_old_positional_shortcuts() {
+        let prompts: Vec<_> = (1..=10).map(|i| json!({ "id": i.to_string(), "title": "t", "content": "c", "color": "red" })).collect();
+        let mut library = document(json!({ "schemaVersion": 1, "prompts": prompts }));
+        library["prompts"][2]["shortcut"] = json!("ctrl+k");
+        migrate_document(&mut library, &PROMPTS_SCHEMA).unwrap();
+
+        let shortcuts: Vec<_> = library["prompts"].as_array().unwrap().iter().map(|p| p.get("shortcut").cloned()).collect();
+        assert_eq!(shortcuts[0], Some(json!("cmd+alt+1")));
+        assert_eq!(shortcuts[2], Some(json!("ctrl+k")));
+        assert_eq!(shortcuts[8], Some(json!("cmd+alt+9")));
+        assert_eq!(shortcuts[9], None);
+        assert_eq!(library[SCHEMA_VERSION_KEY], json!(PROMPTS_SCHEMA.current_version()));
+    }
+
+    #[test]
+    fn prompts_v2_keep_literal_braces_that_arent_templates() {
+        let mut library = document(json!({
+            "schemaVersion": 2,
+            "prompts": [
+                { "id": "1", "title": "t", "content": "Render {{ user.name }} in Jinja", "color": "red" },
+                { "id": "2", "title": "t", "content": "Dated {{date}} for {{app}}", "color": "red" },
+                { "id": "3", "title": "t", "content": "No braces", "color": "red" },
+            ],
+            "folders": [
+                { "id": "f", "name": "Web", "color": "red", "prompts": [
+                    { "id": "4", "title": "t", "content": "{{ user.name }}", "color": "red", "folderId": "f" },
+                ] },
+            ],
+        }));
+        migrate_document(&mut library, &PROMPTS_SCHEMA).unwrap();
+
+        let contents: Vec<_> = library["prompts"].as_array().unwrap().iter().map(|p| p["content"].clone()).collect();
+        assert_eq!(contents, vec![json!(r"Render \{{ user.name }} in Jinja"), json!("Dated {{date}} for {{app}}"), json!("No braces")]);
+        assert_eq!(library["folders"][0]["prompts"][0]["content"], json!(r"\{{ user.name }}"));
+        let segments = crate::template::parse(contents[0].as_str().unwrap()).unwrap();
+        assert_eq!(segments, vec![crate::template::Segment::Text("Render {{ user.name }} in Jinja".to_string())]);
+    }
+
+    #[test]
+    fn failed_migration_leaves_the_document_alone() {
+        let original = document(json!({ "prompts": [1] }));
+        let mut library = original.clone();
+        assert!(migrate_document(&mut library, &PROMPTS_SCHEMA).is_err());
+        assert_eq!(library, original);
+    }
+
+    #[test]
+    fn backup_is_written_next_to_the_store_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prompts.json");
+        assert_eq!(backup_file(&path, "prompts.json", 1), Ok(None));
+
+        std::fs::write(&path, "{\"prompts\":[]}").unwrap();
+        let backup = backup_file(&path, "prompts.json", 1).unwrap().unwrap();
+        assert_eq!(Path::new(&backup), dir.path().join("prompts.json.v1.bak"));
+        assert_eq!(std::fs::read_to_string(backup).unwrap(), "{\"prompts\":[]}");
+    }
+}
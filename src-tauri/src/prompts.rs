@@ -0,0 +1,561 @@
+// Rust-owned prompt repository.
+//
+// Every window used to read and rewrite `prompts.json` on its own through the
+// store plugin, which meant two editor windows saving at the same time could
+// silently drop each other's changes. All reads and writes now go through this
+// module, which serialises access with a single lock, keeps the folder
+// membership lists consistent with each prompt's `folderId`, emits a
+// `prompts-changed` event after every write and re-syncs prompt shortcuts.
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::chords;
+use crate::events;
+use crate::key_macro;
+use crate::shortcuts;
+
+pub const PROMPTS_STORE: &str = "prompts.json";
+
+const DEFAULT_PROMPT_COLOR: &str = "from-blue-500 to-cyan-500";
+const DEFAULT_FOLDER_COLOR: &str = "from-gray-500 to-gray-600";
+
+// Held for the whole load → mutate → save cycle so concurrent commands from
+// different windows can't interleave their writes.
+static REPOSITORY_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Prompt {
+    pub id: String,
+    pub title: String,
+    pub content: String,
+    pub color: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder_id: Option<String>,
+    // Key sequence played around the text, see `key_macro`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_macro: Option<String>,
+    // Global shortcut that injects this prompt, e.g. "cmd+alt+1".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shortcut: Option<String>,
+    // Letters typed after the chord leader to inject this prompt, e.g. "rf".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mnemonic: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Folder {
+    pub id: String,
+    pub name: String,
+    pub color: String,
+    #[serde(default)]
+    pub is_expanded: bool,
+    // Denormalised copy of the prompts whose `folder_id` points here. The UI
+    // reads it directly, so it is rebuilt on every write.
+    #[serde(default)]
+    pub prompts: Vec<Prompt>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PromptLibrary {
+    pub prompts: Vec<Prompt>,
+    pub folders: Vec<Folder>,
+}
+
+// Fields the UI sends when creating or editing a prompt. The id is always
+// assigned or looked up by the backend.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptDraft {
+    pub title: String,
+    pub content: String,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub folder_id: Option<String>,
+    #[serde(default)]
+    pub key_macro: Option<String>,
+    #[serde(default)]
+    pub shortcut: Option<String>,
+    #[serde(default)]
+    pub mnemonic: Option<String>,
+}
+
+fn default_prompts() -> Vec<Prompt> {
+    let defaults = [
+        ("1", "Debug Root Cause", "Come up with 5-7 most likely root causes of this bug, and attempt the 1-2 most likely fixes with proper logging. Don't hold back, give it your all.", "from-purple-500 to-pink-500"),
+        ("2", "Explain Code", "Explain this code in detail, including its purpose, how it works, potential edge cases, and any improvements that could be made.", "from-blue-500 to-cyan-500"),
+        ("3", "Refactor", "Refactor this code to be more readable, maintainable, and performant. Follow best practices and explain your changes.", "from-green-500 to-emerald-500"),
+        ("4", "Write Tests", "Write comprehensive unit tests for this code, covering edge cases and error scenarios. Use appropriate testing patterns.", "from-orange-500 to-red-500"),
+        ("5", "Optimize Performance", "Analyze this code for performance bottlenecks and suggest specific optimizations with examples.", "from-indigo-500 to-purple-500"),
+        ("6", "Add Error Handling", "Add comprehensive error handling to this code with proper logging and user-friendly error messages.", "from-teal-500 to-green-500"),
+    ];
+
+    defaults
+        .iter()
+        .map(|(id, title, content, color)| Prompt {
+            id: id.to_string(),
+            title: title.to_string(),
+            content: content.to_string(),
+            color: color.to_string(),
+            folder_id: None,
+            key_macro: None,
+            shortcut: Some(format!("cmd+alt+{}", id)),
+            mnemonic: None,
+        })
+        .collect()
+}
+
+impl PromptLibrary {
+    fn prompt_index(&self, id: &str) -> Result<usize, String> {
+        self.prompts
+            .iter()
+            .position(|p| p.id == id)
+            .ok_or_else(|| format!("Prompt '{}' not found", id))
+    }
+
+    // Two prompts with the same mnemonic would make the chord ambiguous.
+    fn ensure_mnemonic_free(&self, mnemonic: &Option<String>, except_id: Option<&str>) -> Result<(), String> {
+        if let Some(mnemonic) = mnemonic {
+            if let Some(other) = self
+                .prompts
+                .iter()
+                .find(|p| p.mnemonic.as_ref() == Some(mnemonic) && Some(p.id.as_str()) != except_id)
+            {
+                return Err(format!("Mnemonic '{}' is already used by \"{}\"", mnemonic, other.title));
+            }
+        }
+        Ok(())
+    }
+
+    fn ensure_folder_exists(&self, folder_id: &Option<String>) -> Result<(), String> {
+        if let Some(folder_id) = folder_id {
+            if !self.folders.iter().any(|f| &f.id == folder_id) {
+                return Err(format!("Folder '{}' not found", folder_id));
+            }
+        }
+        Ok(())
+    }
+
+    fn folder_mut(&mut self, folder_id: &str) -> Result<&mut Folder, String> {
+        self.folders
+            .iter_mut()
+            .find(|f| f.id == folder_id)
+            .ok_or_else(|| format!("Folder '{}' not found", folder_id))
+    }
+
+    // Remove a folder. Its prompts stay in the library, out of any folder.
+    fn remove_folder(&mut self, folder_id: &str) -> Result<Folder, String> {
+        let index = self
+            .folders
+            .iter()
+            .position(|f| f.id == folder_id)
+            .ok_or_else(|| format!("Folder '{}' not found", folder_id))?;
+        for prompt in self.prompts.iter_mut() {
+            if prompt.folder_id.as_deref() == Some(folder_id) {
+                prompt.folder_id = None;
+            }
+        }
+        Ok(self.folders.remove(index))
+    }
+
+    // Rebuild every folder's `prompts` list from the prompts' `folder_id`s so
+    // the two views of folder membership can never disagree.
+    fn sync_folders(&mut self) {
+        for prompt in self.prompts.iter_mut() {
+            let dangling = match &prompt.folder_id {
+                Some(folder_id) => !self.folders.iter().any(|f| &f.id == folder_id),
+                None => false,
+            };
+            if dangling {
+                prompt.folder_id = None;
+            }
+        }
+
+        for folder in self.folders.iter_mut() {
+            folder.prompts = self
+                .prompts
+                .iter()
+                .filter(|p| p.folder_id.as_deref() == Some(folder.id.as_str()))
+                .cloned()
+                .collect();
+        }
+    }
+}
+
+// A store that has never held prompts starts out with the defaults; one whose
+// prompts were all deleted stays empty.
+fn load_library(app: &AppHandle) -> Result<PromptLibrary, String> {
+    let store = app
+        .store(PROMPTS_STORE)
+        .map_err(|e| format!("Failed to load prompts store: {}", e))?;
+
+    library_from(store.get("prompts"), store.get("folders"))
+}
+
+fn library_from(prompts: Option<Value>, folders: Option<Value>) -> Result<PromptLibrary, String> {
+    let prompts = match prompts {
+        Some(value) => serde_json::from_value::<Vec<Prompt>>(value)
+            .map_err(|e| format!("Failed to parse prompts: {}", e))?,
+        None => default_prompts(),
+    };
+    let folders = match folders {
+        Some(value) => serde_json::from_value::<Vec<Folder>>(value)
+            .map_err(|e| format!("Failed to parse folders: {}", e))?,
+        None => Vec::new(),
+    };
+
+    Ok(PromptLibrary { prompts, folders })
+}
+
+fn save_library(app: &AppHandle, library: &PromptLibrary) -> Result<(), String> {
+    let store = app
+        .store(PROMPTS_STORE)
+        .map_err(|e| format!("Failed to load prompts store: {}", e))?;
+
+    let prompts = serde_json::to_value(&library.prompts)
+        .map_err(|e| format!("Failed to serialize prompts: {}", e))?;
+    let folders = serde_json::to_value(&library.folders)
+        .map_err(|e| format!("Failed to serialize folders: {}", e))?;
+
+    store.set("prompts", prompts);
+    store.set("folders", folders);
+    store
+        .save()
+        .map_err(|e| format!("Failed to save prompts: {}", e))
+}
+
+// Run `f` against the current library while holding the repository lock,
+// persist the result if it succeeds and notify every window of the change.
+fn with_library<T>(
+    app: &AppHandle,
+    f: impl FnOnce(&mut PromptLibrary) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = REPOSITORY_LOCK.lock().unwrap();
+
+    let before = load_library(app)?;
+    let mut library = before.clone();
+    let result = f(&mut library)?;
+    library.sync_folders();
+    save_library(app, &library)?;
+    events::emit_prompts_changed(app, &before, &library);
+    shortcuts::sync_prompts(app, &library);
+
+    Ok(result)
+}
+
+pub fn list(app: &AppHandle) -> Result<PromptLibrary, String> {
+    let _guard = REPOSITORY_LOCK.lock().unwrap();
+
+    let seeded = app
+        .store(PROMPTS_STORE)
+        .map_err(|e| format!("Failed to load prompts store: {}", e))?
+        .has("prompts");
+    let library = load_library(app)?;
+    if !seeded {
+        println!("📋 No saved prompts, seeding defaults");
+        let before = PromptLibrary {
+            prompts: Vec::new(),
+            folders: library.folders.clone(),
+        };
+        save_library(app, &library)?;
+        events::emit_prompts_changed(app, &before, &library);
+        shortcuts::sync_prompts(app, &library);
+    }
+
+    Ok(library)
+}
+
+pub fn get(app: &AppHandle, id: &str) -> Result<Prompt, String> {
+    let library = list(app)?;
+    let index = library.prompt_index(id)?;
+    Ok(library.prompts[index].clone())
+}
+
+pub fn create(app: &AppHandle, draft: PromptDraft) -> Result<Prompt, String> {
+    with_library(app, |library| {
+        validate_draft(&draft)?;
+        library.ensure_folder_exists(&draft.folder_id)?;
+        let mnemonic = normalize_mnemonic(draft.mnemonic)?;
+        library.ensure_mnemonic_free(&mnemonic, None)?;
+
+        let prompt = Prompt {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: draft.title.trim().to_string(),
+            content: draft.content.trim().to_string(),
+            color: draft.color.unwrap_or_else(|| DEFAULT_PROMPT_COLOR.to_string()),
+            folder_id: draft.folder_id,
+            key_macro: normalize_optional(draft.key_macro),
+            shortcut: normalize_optional(draft.shortcut),
+            mnemonic,
+        };
+        println!("➕ Creating prompt {} ({})", prompt.title, prompt.id);
+        library.prompts.push(prompt.clone());
+        Ok(prompt)
+    })
+}
+
+pub fn update(app: &AppHandle, id: &str, draft: PromptDraft) -> Result<Prompt, String> {
+    with_library(app, |library| {
+        validate_draft(&draft)?;
+        library.ensure_folder_exists(&draft.folder_id)?;
+        let mnemonic = normalize_mnemonic(draft.mnemonic)?;
+        library.ensure_mnemonic_free(&mnemonic, Some(id))?;
+
+        let index = library.prompt_index(id)?;
+        let prompt = &mut library.prompts[index];
+        prompt.title = draft.title.trim().to_string();
+        prompt.content = draft.content.trim().to_string();
+        if let Some(color) = draft.color {
+            prompt.color = color;
+        }
+        prompt.folder_id = draft.folder_id;
+        prompt.key_macro = normalize_optional(draft.key_macro);
+        prompt.shortcut = normalize_optional(draft.shortcut);
+        prompt.mnemonic = mnemonic;
+        println!("✏️ Updated prompt {} ({})", prompt.title, prompt.id);
+        Ok(prompt.clone())
+    })
+}
+
+pub fn delete(app: &AppHandle, id: &str) -> Result<(), String> {
+    with_library(app, |library| {
+        let index = library.prompt_index(id)?;
+        let removed = library.prompts.remove(index);
+        println!("🗑️ Deleted prompt {} ({})", removed.title, removed.id);
+        Ok(())
+    })
+}
+
+// Move a prompt into `folder_id` (or out of any folder when `None`) and,
+// optionally, to a new position in the prompt order.
+pub fn move_to(
+    app: &AppHandle,
+    id: &str,
+    folder_id: Option<String>,
+    position: Option<usize>,
+) -> Result<PromptLibrary, String> {
+    with_library(app, |library| {
+        library.ensure_folder_exists(&folder_id)?;
+
+        let index = library.prompt_index(id)?;
+        let mut prompt = library.prompts.remove(index);
+        prompt.folder_id = folder_id;
+
+        let position = position.unwrap_or(index).min(library.prompts.len());
+        println!("↔️ Moving prompt {} to position {}", prompt.id, position);
+        library.prompts.insert(position, prompt);
+        Ok(())
+    })?;
+
+    list(app)
+}
+
+pub fn create_folder(app: &AppHandle, name: &str, prompt_ids: Vec<String>) -> Result<Folder, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Folder name cannot be empty".to_string());
+    }
+
+    let folder_id = with_library(app, |library| {
+        for prompt_id in &prompt_ids {
+            library.prompt_index(prompt_id)?;
+        }
+
+        let folder = Folder {
+            id: format!("folder_{}", uuid::Uuid::new_v4()),
+            name: name.to_string(),
+            color: DEFAULT_FOLDER_COLOR.to_string(),
+            is_expanded: true,
+            prompts: Vec::new(),
+        };
+        println!("📁 Creating folder {} ({})", folder.name, folder.id);
+
+        for prompt in library.prompts.iter_mut() {
+            if prompt_ids.contains(&prompt.id) {
+                prompt.folder_id = Some(folder.id.clone());
+            }
+        }
+
+        let folder_id = folder.id.clone();
+        library.folders.push(folder);
+        Ok(folder_id)
+    })?;
+
+    list(app)?
+        .folders
+        .into_iter()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| format!("Folder '{}' not found after creation", folder_id))
+}
+
+pub fn toggle_folder(app: &AppHandle, folder_id: &str) -> Result<Folder, String> {
+    with_library(app, |library| {
+        let folder = library.folder_mut(folder_id)?;
+        folder.is_expanded = !folder.is_expanded;
+        Ok(())
+    })?;
+
+    // Read back what was saved, with the membership list `with_library`
+    // rebuilt, rather than the folder as it was before the sync.
+    list(app)?
+        .folders
+        .into_iter()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| format!("Folder '{}' not found after toggling", folder_id))
+}
+
+pub fn rename_folder(app: &AppHandle, folder_id: &str, name: &str) -> Result<Folder, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Folder name cannot be empty".to_string());
+    }
+
+    with_library(app, |library| {
+        let folder = library.folder_mut(folder_id)?;
+        println!("✏️ Renaming folder {} to {} ({})", folder.name, name, folder.id);
+        folder.name = name.to_string();
+        Ok(())
+    })?;
+
+    list(app)?
+        .folders
+        .into_iter()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| format!("Folder '{}' not found after renaming", folder_id))
+}
+
+// Delete a folder, keeping the prompts that were in it.
+pub fn delete_folder(app: &AppHandle, folder_id: &str) -> Result<(), String> {
+    with_library(app, |library| {
+        let removed = library.remove_folder(folder_id)?;
+        println!("🗑️ Deleted folder {} ({})", removed.name, removed.id);
+        Ok(())
+    })
+}
+
+fn validate_draft(draft: &PromptDraft) -> Result<(), String> {
+    if draft.title.trim().is_empty() || draft.content.trim().is_empty() {
+        return Err("Prompt title and content cannot be empty".to_string());
+    }
+    if let Some(script) = &draft.key_macro {
+        key_macro::parse(script)?;
+    }
+    if let Some(shortcut) = normalize_optional(draft.shortcut.clone()) {
+        shortcuts::parse(&shortcut)?;
+    }
+    Ok(())
+}
+
+fn normalize_mnemonic(mnemonic: Option<String>) -> Result<Option<String>, String> {
+    normalize_optional(mnemonic)
+        .map(|m| chords::normalize_mnemonic(&m))
+        .transpose()
+}
+
+fn normalize_optional(value: Option<String>) -> Option<String> {
+    value
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn prompt(id: &str, folder_id: Option<&str>) -> Prompt {
+        Prompt {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: String::new(),
+            color: String::new(),
+            folder_id: folder_id.map(str::to_string),
+            key_macro: None,
+            shortcut: None,
+            mnemonic: None,
+        }
+    }
+
+    fn folder(id: &str) -> Folder {
+        Folder {
+            id: id.to_string(),
+            name: id.to_string(),
+            color: String::new(),
+            is_expanded: true,
+            prompts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn missing_prompts_key_seeds_the_defaults() {
+        let library = library_from(None, None).unwrap();
+        assert_eq!(library.prompts, default_prompts());
+        assert_eq!(library.prompts.len(), 6);
+        assert!(library.folders.is_empty());
+    }
+
+    #[test]
+    fn missing_prompts_key_keeps_saved_folders() {
+        let library = library_from(None, Some(json!([{ "id": "f", "name": "Work", "color": "" }]))).unwrap();
+        assert_eq!(library.prompts, default_prompts());
+        assert_eq!(library.folders, [Folder { name: "Work".to_string(), is_expanded: false, ..folder("f") }]);
+    }
+
+    #[test]
+    fn emptied_library_stays_empty() {
+        let library = library_from(Some(json!([])), Some(json!([]))).unwrap();
+        assert!(library.prompts.is_empty());
+        assert!(library.folders.is_empty());
+    }
+
+    #[test]
+    fn saved_prompts_replace_the_defaults() {
+        let saved = json!([{ "id": "a", "title": "A", "content": "text", "color": "c" }]);
+        let library = library_from(Some(saved), None).unwrap();
+        assert_eq!(library.prompts.len(), 1);
+        assert_eq!(library.prompts[0].id, "a");
+    }
+
+    #[test]
+    fn malformed_prompts_are_an_error() {
+        let error = library_from(Some(json!({ "not": "a list" })), None).unwrap_err();
+        assert!(error.starts_with("Failed to parse prompts:"), "{}", error);
+    }
+
+    #[test]
+    fn removing_a_folder_keeps_its_prompts() {
+        let mut library = PromptLibrary {
+            prompts: vec![prompt("a", Some("f")), prompt("b", Some("g")), prompt("c", None)],
+            folders: vec![folder("f"), folder("g")],
+        };
+        library.sync_folders();
+
+        assert_eq!(library.remove_folder("f").unwrap().id, "f");
+        library.sync_folders();
+        assert_eq!(library.prompts.len(), 3);
+        assert_eq!(library.prompts[0].folder_id, None);
+        assert_eq!(library.prompts[1].folder_id.as_deref(), Some("g"));
+        assert_eq!(library.folders.len(), 1);
+        assert_eq!(library.folders[0].prompts, [prompt("b", Some("g"))]);
+
+        assert_eq!(library.remove_folder("f").unwrap_err(), "Folder 'f' not found");
+    }
+
+    #[test]
+    fn sync_clears_prompts_pointing_at_missing_folders() {
+        let mut library = PromptLibrary {
+            prompts: vec![prompt("a", Some("gone"))],
+            folders: vec![folder("f")],
+        };
+        library.sync_folders();
+        assert_eq!(library.prompts[0].folder_id, None);
+        assert!(library.folders[0].prompts.is_empty());
+    }
+}
@@ -0,0 +1,259 @@
+// Shortcut recorder: capture the next key combination instead of making the
+// user type `alt+shift+space` by hand.
+//
+// `record_shortcut` starts a recording and waits. The window doing the
+// recording answers with `submit_recorded_keys` for every keydown it sees; a
+// combination Prompt Buddy already has registered never reaches the webview,
+// so while recording the global handlers hand their own shortcut over instead
+// of acting on it. Either way the result is normalised into the format
+// `Shortcut::from_str` accepts and checked against the existing bindings.
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri_plugin_global_shortcut::{Modifiers, Shortcut};
+
+use crate::shortcuts::{self, Binding};
+
+// Give up if nothing is pressed, so an abandoned recording doesn't keep
+// intercepting shortcuts.
+const RECORD_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[cfg(target_os = "macos")]
+const META_NAME: &str = "cmd";
+#[cfg(not(target_os = "macos"))]
+const META_NAME: &str = "super";
+
+struct Recording {
+    binding: Binding,
+    // `None` means the recording was cancelled.
+    reply: Sender<Option<String>>,
+}
+
+static RECORDING: Lazy<Mutex<Option<Recording>>> = Lazy::new(|| Mutex::new(None));
+
+// A keydown as the webview saw it. `code` is `KeyboardEvent.code`, which
+// names the physical key whatever the keyboard layout.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyCombo {
+    pub code: String,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedShortcut {
+    pub shortcut: String,
+    // The binding that already uses these keys, e.g. `prompt "Refactor"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflicts_with: Option<String>,
+}
+
+fn key_name(code: &str) -> Option<String> {
+    let name = match code {
+        "ShiftLeft" | "ShiftRight" | "ControlLeft" | "ControlRight" | "AltLeft" | "AltRight" | "MetaLeft"
+        | "MetaRight" | "OSLeft" | "OSRight" | "" => return None,
+        "ArrowUp" => "up".to_string(),
+        "ArrowDown" => "down".to_string(),
+        "ArrowLeft" => "left".to_string(),
+        "ArrowRight" => "right".to_string(),
+        _ => {
+            if let Some(letter) = code.strip_prefix("Key") {
+                letter.to_lowercase()
+            } else if let Some(digit) = code.strip_prefix("Digit") {
+                digit.to_string()
+            } else {
+                code.to_lowercase()
+            }
+        }
+    };
+    Some(name)
+}
+
+fn is_function_key(key: &str) -> bool {
+    key.strip_prefix('f').is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+// Turn a keydown into a shortcut string. `Ok(None)` means it was only a
+// modifier, so the recording should keep waiting for the actual key.
+pub fn normalize(combo: &KeyCombo) -> Result<Option<String>, String> {
+    let Some(key) = key_name(&combo.code) else {
+        return Ok(None);
+    };
+
+    let mut parts = Vec::new();
+    if combo.meta {
+        parts.push(META_NAME);
+    }
+    if combo.ctrl {
+        parts.push("ctrl");
+    }
+    if combo.alt {
+        parts.push("alt");
+    }
+    if combo.shift {
+        parts.push("shift");
+    }
+    // A bare letter as a global shortcut would swallow normal typing.
+    if parts.is_empty() && !is_function_key(&key) {
+        return Err(format!("Add a modifier such as ctrl or alt to '{}'", key));
+    }
+    parts.push(&key);
+
+    let shortcut = parts.join("+");
+    shortcuts::parse(&shortcut)?;
+    Ok(Some(shortcut))
+}
+
+// The keydown that produces an already registered shortcut, so it can go
+// through `normalize` like a combination the webview saw.
+fn combo_of(shortcut: &Shortcut) -> KeyCombo {
+    KeyCombo {
+        code: shortcut.key.to_string(),
+        ctrl: shortcut.mods.contains(Modifiers::CONTROL),
+        alt: shortcut.mods.contains(Modifiers::ALT),
+        shift: shortcut.mods.contains(Modifiers::SHIFT),
+        meta: shortcut.mods.contains(Modifiers::SUPER),
+    }
+}
+
+// Rewrite a shortcut as the user wrote it (`Shift+Alt+Space`) in the same
+// form `normalize` produces (`alt+shift+space`).
+fn canonical(label: &str) -> String {
+    shortcuts::parse(label)
+        .ok()
+        .and_then(|shortcut| normalize(&combo_of(&shortcut)).ok().flatten())
+        .unwrap_or_else(|| label.trim().to_string())
+}
+
+// Record the next key combination for `binding`. Returns `Ok(None)` if the
+// recording was cancelled.
+pub async fn record(binding: Binding) -> Result<Option<RecordedShortcut>, String> {
+    let (reply, receiver) = mpsc::channel();
+    // Only one recording at a time: a newer one cancels the one still open.
+    if let Some(previous) = RECORDING.lock().unwrap().replace(Recording { binding: binding.clone(), reply }) {
+        println!("⚠️ Replacing unfinished shortcut recording for {:?}", previous.binding);
+        let _ = previous.reply.send(None);
+    }
+    println!("⏺️  Recording shortcut for {:?}", binding);
+
+    let recorded = tauri::async_runtime::spawn_blocking(move || match receiver.recv_timeout(RECORD_TIMEOUT) {
+        Ok(recorded) => Ok(recorded),
+        Err(RecvTimeoutError::Timeout) => Err("No shortcut was pressed".to_string()),
+        Err(RecvTimeoutError::Disconnected) => Ok(None),
+    })
+    .await
+    .map_err(|e| format!("Failed to wait for shortcut: {}", e))?;
+
+    if recorded.is_err() {
+        // Stop intercepting, unless a newer recording already took over.
+        let mut recording = RECORDING.lock().unwrap();
+        if recording.as_ref().is_some_and(|r| r.binding == binding) {
+            *recording = None;
+        }
+    }
+
+    let Some(shortcut) = recorded? else {
+        println!("⏹️  Shortcut recording cancelled");
+        return Ok(None);
+    };
+    let conflicts_with = shortcuts::owner_of(shortcuts::parse(&shortcut)?, &binding);
+    println!("⏺️  Recorded {} (conflicts with: {:?})", shortcut, conflicts_with);
+    Ok(Some(RecordedShortcut { shortcut, conflicts_with }))
+}
+
+fn finish(shortcut: Option<String>) -> bool {
+    match RECORDING.lock().unwrap().take() {
+        Some(recording) => {
+            let _ = recording.reply.send(shortcut);
+            true
+        }
+        None => false,
+    }
+}
+
+// A keydown from the recording window. Modifier-only presses are ignored;
+// an unusable combination is reported without ending the recording.
+pub fn submit(combo: KeyCombo) -> Result<(), String> {
+    if RECORDING.lock().unwrap().is_none() {
+        return Err("No shortcut is being recorded".to_string());
+    }
+    if let Some(shortcut) = normalize(&combo)? {
+        finish(Some(shortcut));
+    }
+    Ok(())
+}
+
+pub fn cancel() {
+    finish(None);
+}
+
+// Called by every global shortcut handler first. While recording, the
+// shortcut is taken as the recorded one and the handler should do nothing.
+pub fn intercept(label: &str) -> bool {
+    let intercepted = finish(Some(canonical(label)));
+    if intercepted {
+        println!("⏺️  Intercepted {} for recording", label);
+    }
+    intercepted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn combo(code: &str, ctrl: bool, alt: bool, shift: bool, meta: bool) -> KeyCombo {
+        KeyCombo { code: code.to_string(), ctrl, alt, shift, meta }
+    }
+
+    #[test]
+    fn modifier_only_presses_keep_waiting() {
+        assert_eq!(normalize(&combo("ShiftLeft", false, false, true, false)), Ok(None));
+        assert_eq!(normalize(&combo("ControlRight", true, false, false, false)), Ok(None));
+        assert_eq!(normalize(&combo("MetaLeft", false, false, false, true)), Ok(None));
+    }
+
+    #[test]
+    fn bare_letters_need_a_modifier() {
+        assert_eq!(
+            normalize(&combo("KeyA", false, false, false, false)),
+            Err("Add a modifier such as ctrl or alt to 'a'".to_string())
+        );
+    }
+
+    #[test]
+    fn bare_function_keys_are_allowed() {
+        assert_eq!(normalize(&combo("F5", false, false, false, false)), Ok(Some("f5".to_string())));
+        assert_eq!(normalize(&combo("F12", false, false, false, false)), Ok(Some("f12".to_string())));
+    }
+
+    #[test]
+    fn codes_map_to_shortcut_key_names() {
+        assert_eq!(normalize(&combo("ArrowUp", true, false, false, false)), Ok(Some("ctrl+up".to_string())));
+        assert_eq!(normalize(&combo("ArrowLeft", false, true, false, false)), Ok(Some("alt+left".to_string())));
+        assert_eq!(normalize(&combo("Digit1", false, true, false, false)), Ok(Some("alt+1".to_string())));
+        assert_eq!(normalize(&combo("KeyK", true, false, true, false)), Ok(Some("ctrl+shift+k".to_string())));
+        assert_eq!(normalize(&combo("Space", false, true, true, false)), Ok(Some("alt+shift+space".to_string())));
+    }
+
+    #[test]
+    fn modifiers_come_out_in_a_fixed_order() {
+        assert_eq!(
+            normalize(&combo("KeyP", true, true, true, true)),
+            Ok(Some(format!("{}+ctrl+alt+shift+p", META_NAME)))
+        );
+    }
+
+    #[test]
+    fn intercepted_labels_are_normalized() {
+        assert_eq!(canonical("Shift+Alt+Space"), "alt+shift+space");
+        assert_eq!(canonical("  super+ctrl+KeyK "), format!("{}+ctrl+k", META_NAME));
+    }
+}
@@ -0,0 +1,368 @@
+// Routing rules: per-app and per-window overrides for where and how prompts
+// are injected.
+//
+// Settings hold an ordered list of rules. Each one matches the app that was
+// frontmost when the bar opened, by app name or window title, with a glob
+// (`*` and `?`, case-insensitive) or a regex. A matching rule can pick the app
+// to inject into, the injection strategy, the newline policy and the prompt
+// folder the bar puts first. Rules are tried in order and every setting comes
+// from the first matching rule that sets it, so a specific rule can sit above
+// a broad one and only override part of it.
+//
+// Electron apps show up as "Electron", so app-name rules are also tried
+// against the app's bundle path; the default rules use that to name DaVinci
+// Resolve and Qoder.
+//
+// Matching is a pure function of the rules and an `AppContext`, so it can be
+// exercised without any windows around.
+
+use once_cell::sync::Lazy;
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchField {
+    #[default]
+    AppName,
+    WindowTitle,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum PatternSyntax {
+    #[default]
+    Glob,
+    Regex,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RoutingRule {
+    // Shown in settings only.
+    pub name: String,
+    pub enabled: bool,
+    pub field: MatchField,
+    pub syntax: PatternSyntax,
+    pub pattern: String,
+    // What a match changes; `None` leaves the setting alone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_app: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub injection_strategy: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newline_policy: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder_id: Option<String>,
+}
+
+impl Default for RoutingRule {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            enabled: true,
+            field: MatchField::AppName,
+            syntax: PatternSyntax::Glob,
+            pattern: String::new(),
+            target_app: None,
+            injection_strategy: None,
+            newline_policy: None,
+            folder_id: None,
+        }
+    }
+}
+
+// What is known about the app the bar was opened over.
+#[derive(Debug, Clone, Default)]
+pub struct AppContext {
+    pub app_name: String,
+    pub bundle_path: Option<String>,
+    pub window_title: Option<String>,
+}
+
+// The combined outcome of every matching rule.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Route {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_app: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub injection_strategy: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newline_policy: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder_id: Option<String>,
+}
+
+// The rules currently in the settings. Kept here because the frontmost app is
+// captured from places that have no `AppHandle` to load settings with.
+static RULES: Lazy<Mutex<Vec<RoutingRule>>> = Lazy::new(|| Mutex::new(default_rules()));
+
+// Electron apps that used to be special-cased in app detection.
+pub fn default_rules() -> Vec<RoutingRule> {
+    vec![
+        RoutingRule {
+            name: "DaVinci Resolve".to_string(),
+            pattern: "*DaVinci Resolve*".to_string(),
+            target_app: Some("DaVinci Resolve".to_string()),
+            ..RoutingRule::default()
+        },
+        RoutingRule {
+            name: "Qoder".to_string(),
+            pattern: "*qoder*".to_string(),
+            target_app: Some("Qoder".to_string()),
+            ..RoutingRule::default()
+        },
+    ]
+}
+
+pub fn set_rules(rules: Vec<RoutingRule>) {
+    *RULES.lock().unwrap() = rules;
+}
+
+pub fn rules() -> Vec<RoutingRule> {
+    RULES.lock().unwrap().clone()
+}
+
+// Glob match on whole strings, ignoring case. `*` matches any run of
+// characters and `?` exactly one.
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    // Classic backtracking over the last `*` seen.
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+fn pattern_matches(rule: &RoutingRule, text: &str) -> Result<bool, String> {
+    match rule.syntax {
+        PatternSyntax::Glob => Ok(glob_matches(&rule.pattern, text)),
+        PatternSyntax::Regex => RegexBuilder::new(&rule.pattern)
+            .case_insensitive(true)
+            .build()
+            .map(|regex| regex.is_match(text))
+            .map_err(|e| format!("Invalid regex '{}': {}", rule.pattern, e)),
+    }
+}
+
+fn rule_matches(rule: &RoutingRule, context: &AppContext) -> Result<bool, String> {
+    if !rule.enabled || rule.pattern.trim().is_empty() {
+        return Ok(false);
+    }
+    match rule.field {
+        MatchField::AppName => {
+            if pattern_matches(rule, &context.app_name)? {
+                return Ok(true);
+            }
+            match &context.bundle_path {
+                Some(path) => pattern_matches(rule, path),
+                None => Ok(false),
+            }
+        }
+        MatchField::WindowTitle => match &context.window_title {
+            Some(title) => pattern_matches(rule, title),
+            None => Ok(false),
+        },
+    }
+}
+
+// Fold every matching rule, in order, into one route.
+pub fn route(rules: &[RoutingRule], context: &AppContext) -> Route {
+    let mut route = Route::default();
+    for rule in rules {
+        match rule_matches(rule, context) {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(e) => {
+                println!("⚠️ Skipping routing rule '{}': {}", rule.name, e);
+                continue;
+            }
+        }
+        println!("🧭 Routing rule '{}' matches {}", rule.name, context.app_name);
+        route.target_app = route.target_app.or_else(|| non_empty(&rule.target_app));
+        route.injection_strategy = route.injection_strategy.or_else(|| non_empty(&rule.injection_strategy));
+        route.newline_policy = route.newline_policy.or_else(|| non_empty(&rule.newline_policy));
+        route.folder_id = route.folder_id.or_else(|| non_empty(&rule.folder_id));
+    }
+    route
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string)
+}
+
+// The app name to use for `context`: the first matching rule's target, or the
+// detected name.
+pub fn resolve_app_name(context: &AppContext) -> String {
+    route(&rules(), context).target_app.unwrap_or_else(|| context.app_name.clone())
+}
+
+// Check rules before they are saved, collecting every problem.
+pub fn validate(rules: &[RoutingRule]) -> Result<(), String> {
+    let mut errors = Vec::new();
+    for (index, rule) in rules.iter().enumerate() {
+        let label = if rule.name.trim().is_empty() {
+            format!("rule {}", index + 1)
+        } else {
+            format!("rule '{}'", rule.name)
+        };
+        if rule.pattern.trim().is_empty() {
+            errors.push(format!("{} has no pattern", label));
+        } else if let Err(e) = pattern_matches(rule, "") {
+            errors.push(format!("{}: {}", label, e));
+        }
+        if let Some(strategy) = non_empty(&rule.injection_strategy) {
+            if !["type", "paste", "auto"].contains(&strategy.as_str()) {
+                errors.push(format!("{}: unknown injection strategy '{}'", label, strategy));
+            }
+        }
+        if let Some(policy) = non_empty(&rule.newline_policy) {
+            if crate::injection::NewlinePolicy::parse(&policy).is_none() {
+                errors.push(format!("{}: unknown newline policy '{}'", label, policy));
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Invalid routing rules: {}", errors.join("; ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, pattern: &str) -> RoutingRule {
+        RoutingRule {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            ..RoutingRule::default()
+        }
+    }
+
+    fn app(name: &str) -> AppContext {
+        AppContext {
+            app_name: name.to_string(),
+            ..AppContext::default()
+        }
+    }
+
+    #[test]
+    fn glob_backtracks_over_stars() {
+        assert!(glob_matches("*Agent", "CoreServicesUIAgent"));
+        assert!(glob_matches("*agent", "AgentAgent"));
+        assert!(!glob_matches("*Agent", "Agents"));
+        assert!(glob_matches("a*b*c", "aXbYbZc"));
+        assert!(glob_matches("a*b*c", "abc"));
+        assert!(!glob_matches("a*b*c", "acb"));
+        assert!(glob_matches("?ursor", "Cursor"));
+        assert!(!glob_matches("?ursor", "ursor"));
+    }
+
+    #[test]
+    fn regex_ignores_case() {
+        let rule = RoutingRule {
+            syntax: PatternSyntax::Regex,
+            ..rule("slack", "^slack$")
+        };
+        assert_eq!(pattern_matches(&rule, "Slack"), Ok(true));
+        assert_eq!(pattern_matches(&rule, "Slackware"), Ok(false));
+    }
+
+    #[test]
+    fn validate_rejects_invalid_regex_and_empty_patterns() {
+        let rules = vec![
+            RoutingRule {
+                syntax: PatternSyntax::Regex,
+                ..rule("broken", "(unclosed")
+            },
+            rule("", " "),
+            RoutingRule {
+                injection_strategy: Some("shout".to_string()),
+                ..rule("strategy", "*")
+            },
+        ];
+        let error = validate(&rules).unwrap_err();
+        assert!(error.contains("rule 'broken': Invalid regex '(unclosed'"), "{}", error);
+        assert!(error.contains("rule 2 has no pattern"), "{}", error);
+        assert!(error.contains("unknown injection strategy 'shout'"), "{}", error);
+        assert_eq!(validate(&default_rules()), Ok(()));
+    }
+
+    #[test]
+    fn first_matching_rule_wins_per_field() {
+        let rules = vec![
+            RoutingRule {
+                newline_policy: Some("enter".to_string()),
+                ..rule("terminals", "*term*")
+            },
+            RoutingRule {
+                enabled: false,
+                injection_strategy: Some("type".to_string()),
+                ..rule("disabled", "*")
+            },
+            RoutingRule {
+                injection_strategy: Some("paste".to_string()),
+                newline_policy: Some("spaces".to_string()),
+                ..rule("everything", "*")
+            },
+        ];
+        let route = route(&rules, &app("iTerm2"));
+        assert_eq!(route.newline_policy.as_deref(), Some("enter"));
+        assert_eq!(route.injection_strategy.as_deref(), Some("paste"));
+        assert_eq!(route.target_app, None);
+    }
+
+    #[test]
+    fn window_title_rules_need_a_title() {
+        let rules = vec![RoutingRule {
+            field: MatchField::WindowTitle,
+            folder_id: Some("reviews".to_string()),
+            ..rule("pull requests", "*Pull Request*")
+        }];
+        let mut context = app("Safari");
+        assert_eq!(route(&rules, &context).folder_id, None);
+        context.window_title = Some("Fix focus · Pull Request #12".to_string());
+        assert_eq!(route(&rules, &context).folder_id.as_deref(), Some("reviews"));
+    }
+
+    #[test]
+    fn default_rules_name_electron_apps_from_their_bundle() {
+        let resolve = |bundle: &str| {
+            let context = AppContext {
+                app_name: "Electron".to_string(),
+                bundle_path: Some(bundle.to_string()),
+                window_title: None,
+            };
+            route(&default_rules(), &context).target_app
+        };
+        assert_eq!(resolve("/Applications/DaVinci Resolve/DaVinci Resolve.app").as_deref(), Some("DaVinci Resolve"));
+        assert_eq!(resolve("Macintosh HD:Applications:Qoder.app:").as_deref(), Some("Qoder"));
+        assert_eq!(resolve("/Applications/Obsidian.app"), None);
+    }
+}
@@ -0,0 +1,327 @@
+// Fuzzy search over the prompt library.
+//
+// Scoring follows the usual fuzzy-finder approach: every query character must
+// appear in order in the text, and matches score higher when they are
+// consecutive or start a word. Each whitespace-separated query term must match
+// somewhere in the prompt's title, folder name or content; title matches count
+// the most. Match positions are returned so the UI can highlight them.
+//
+// Matching is a single forward scan plus a backward scan per field, so searching
+// a few thousand prompts stays well under a frame.
+
+use serde::Serialize;
+
+use crate::prompts::{Prompt, PromptLibrary};
+
+const SCORE_MATCH: i64 = 16;
+const BONUS_BOUNDARY: i64 = 10;
+const BONUS_CONSECUTIVE: i64 = 6;
+const BONUS_FIRST_CHAR: i64 = 8;
+const PENALTY_GAP_START: i64 = 3;
+const PENALTY_GAP_EXTENSION: i64 = 1;
+
+const TITLE_WEIGHT: f64 = 3.0;
+const FOLDER_WEIGHT: f64 = 2.0;
+const CONTENT_WEIGHT: f64 = 1.0;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub prompt: Prompt,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder_name: Option<String>,
+    pub score: f64,
+    // Character (not byte) indices of matched characters in each field.
+    pub title_matches: Vec<usize>,
+    pub content_matches: Vec<usize>,
+    pub folder_matches: Vec<usize>,
+}
+
+struct FieldMatch {
+    score: i64,
+    positions: Vec<usize>,
+}
+
+// A field prepared once per search: the original characters for boundary
+// detection and a lowercased copy for matching.
+struct Field {
+    chars: Vec<char>,
+    lower: Vec<char>,
+}
+
+impl Field {
+    fn new(text: &str) -> Self {
+        let chars: Vec<char> = text.chars().collect();
+        let lower = chars.iter().map(|c| fold_case(*c)).collect();
+        Self { chars, lower }
+    }
+}
+
+fn fold_case(c: char) -> char {
+    if c.is_ascii() {
+        return c.to_ascii_lowercase();
+    }
+    // Only simple one-to-one lowercase mappings, so indices stay aligned.
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
+
+// Cheap check, without allocating, that `pattern` occurs as a subsequence of
+// `text`. Most prompts in a large library fail this for any given term.
+fn contains_subsequence(pattern: &[char], text: &str) -> bool {
+    let mut pattern = pattern.iter().peekable();
+    for c in text.chars() {
+        match pattern.peek() {
+            Some(&&p) if fold_case(c) == p => {
+                pattern.next();
+            }
+            Some(_) => {}
+            None => break,
+        }
+    }
+    pattern.peek().is_none()
+}
+
+fn is_boundary(chars: &[char], index: usize) -> bool {
+    if index == 0 {
+        return true;
+    }
+    let prev = chars[index - 1];
+    let current = chars[index];
+    !prev.is_alphanumeric() || (prev.is_lowercase() && current.is_uppercase())
+}
+
+// Match `pattern` (already lowercased) against `text`, preparing the field
+// only when the pattern can actually match.
+fn match_text(pattern: &[char], text: &str, field: &mut Option<Field>) -> Option<FieldMatch> {
+    if !contains_subsequence(pattern, text) {
+        return None;
+    }
+    match_field(pattern, field.get_or_insert_with(|| Field::new(text)))
+}
+
+// Match `pattern` (already lowercased) against `field`.
+//
+// The forward scan finds the earliest position where the whole pattern has
+// matched; the backward scan then walks from that end to the latest possible
+// start, which yields the tightest occurrence ending there.
+fn match_field(pattern: &[char], field: &Field) -> Option<FieldMatch> {
+    if pattern.is_empty() {
+        return Some(FieldMatch { score: 0, positions: Vec::new() });
+    }
+
+    let text = &field.lower;
+    let mut pi = 0;
+    let mut end = None;
+    for (ti, c) in text.iter().enumerate() {
+        if *c == pattern[pi] {
+            pi += 1;
+            if pi == pattern.len() {
+                end = Some(ti);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut pi = pattern.len();
+    let mut ti = end + 1;
+    while pi > 0 {
+        ti -= 1;
+        if text[ti] == pattern[pi - 1] {
+            positions.push(ti);
+            pi -= 1;
+        }
+    }
+    positions.reverse();
+
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    for (i, &pos) in positions.iter().enumerate() {
+        score += SCORE_MATCH;
+        if is_boundary(&field.chars, pos) {
+            score += if i == 0 { BONUS_BOUNDARY + BONUS_FIRST_CHAR } else { BONUS_BOUNDARY };
+        }
+        if let Some(prev) = previous {
+            let gap = pos - prev - 1;
+            if gap == 0 {
+                score += BONUS_CONSECUTIVE;
+            } else {
+                score -= PENALTY_GAP_START + PENALTY_GAP_EXTENSION * (gap as i64 - 1);
+            }
+        }
+        previous = Some(pos);
+    }
+
+    Some(FieldMatch { score, positions })
+}
+
+fn merge_positions(into: &mut Vec<usize>, positions: Vec<usize>) {
+    into.extend(positions);
+    into.sort_unstable();
+    into.dedup();
+}
+
+pub fn search(library: &PromptLibrary, query: &str, limit: usize) -> Vec<SearchResult> {
+    let terms: Vec<Vec<char>> = query
+        .split_whitespace()
+        .map(|term| term.chars().map(fold_case).collect())
+        .collect();
+
+    let mut results: Vec<SearchResult> = library
+        .prompts
+        .iter()
+        .filter_map(|prompt| {
+            let folder_name = prompt
+                .folder_id
+                .as_ref()
+                .and_then(|id| library.folders.iter().find(|f| &f.id == id))
+                .map(|f| f.name.clone());
+
+            let mut score = 0.0;
+            let mut title_matches = Vec::new();
+            let mut content_matches = Vec::new();
+            let mut folder_matches = Vec::new();
+
+            let mut title = None;
+            let mut content = None;
+            let mut folder = None;
+
+            for term in &terms {
+                let title_match = match_text(term, &prompt.title, &mut title);
+                let content_match = match_text(term, &prompt.content, &mut content);
+                let folder_match = folder_name
+                    .as_deref()
+                    .and_then(|name| match_text(term, name, &mut folder));
+
+                if title_match.is_none() && content_match.is_none() && folder_match.is_none() {
+                    return None;
+                }
+
+                // A term counts once, for whichever field it matches best, but
+                // every field it matched is highlighted.
+                let mut best = 0.0f64;
+                if let Some(m) = title_match {
+                    best = best.max(m.score as f64 * TITLE_WEIGHT);
+                    merge_positions(&mut title_matches, m.positions);
+                }
+                if let Some(m) = folder_match {
+                    best = best.max(m.score as f64 * FOLDER_WEIGHT);
+                    merge_positions(&mut folder_matches, m.positions);
+                }
+                if let Some(m) = content_match {
+                    best = best.max(m.score as f64 * CONTENT_WEIGHT);
+                    merge_positions(&mut content_matches, m.positions);
+                }
+                score += best;
+            }
+
+            Some(SearchResult {
+                prompt: prompt.clone(),
+                folder_name,
+                score,
+                title_matches,
+                content_matches,
+                folder_matches,
+            })
+        })
+        .collect();
+
+    // Stable sort: equal scores keep the library order.
+    results.sort_by(|a, b| b.score.total_cmp(&a.score));
+    results.truncate(limit);
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompts::Folder;
+
+    fn prompt(id: &str, title: &str, content: &str, folder_id: Option<&str>) -> Prompt {
+        Prompt {
+            id: id.to_string(),
+            title: title.to_string(),
+            content: content.to_string(),
+            color: String::new(),
+            folder_id: folder_id.map(str::to_string),
+            key_macro: None,
+            shortcut: None,
+            mnemonic: None,
+        }
+    }
+
+    fn library(prompts: Vec<Prompt>) -> PromptLibrary {
+        PromptLibrary {
+            prompts,
+            folders: vec![Folder {
+                id: "f".to_string(),
+                name: "Debugging".to_string(),
+                color: String::new(),
+                is_expanded: false,
+                prompts: Vec::new(),
+            }],
+        }
+    }
+
+    fn ids(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.prompt.id.as_str()).collect()
+    }
+
+    #[test]
+    fn empty_query_lists_the_library_in_order() {
+        let library = library(vec![prompt("1", "b", "", None), prompt("2", "a", "", None)]);
+        let results = search(&library, "   ", 10);
+        assert_eq!(ids(&results), vec!["1", "2"]);
+        assert!(results.iter().all(|r| r.score == 0.0 && r.title_matches.is_empty()));
+    }
+
+    #[test]
+    fn title_matches_rank_above_content_matches() {
+        let library = library(vec![
+            prompt("content", "Explain", "Write tests for this", None),
+            prompt("title", "Write Tests", "Cover the edge cases", None),
+        ]);
+        let results = search(&library, "tests", 10);
+        assert_eq!(ids(&results), vec!["title", "content"]);
+        assert_eq!(results[0].title_matches, vec![6, 7, 8, 9, 10]);
+        assert_eq!(results[1].content_matches, vec![6, 7, 8, 9, 10]);
+    }
+
+    #[test]
+    fn every_term_must_match_some_field() {
+        let library = library(vec![
+            prompt("both", "Root cause", "Find the bug", Some("f")),
+            prompt("one", "Root cause", "Find it", None),
+        ]);
+        let results = search(&library, "root debug", 10);
+        assert_eq!(ids(&results), vec!["both"]);
+        assert_eq!(results[0].folder_name.as_deref(), Some("Debugging"));
+        assert_eq!(results[0].folder_matches, vec![0, 1, 2, 3, 4]);
+        assert!(search(&library, "root zebra", 10).is_empty());
+    }
+
+    #[test]
+    fn consecutive_word_start_matches_score_higher() {
+        let library = library(vec![prompt("scattered", "Rename files", "", None), prompt("tight", "Refactor", "", None)]);
+        assert_eq!(ids(&search(&library, "ref", 10)), vec!["tight", "scattered"]);
+    }
+
+    #[test]
+    fn results_are_capped_at_the_limit() {
+        let prompts = (0..5).map(|i| prompt(&i.to_string(), "Review", "", None)).collect();
+        assert_eq!(search(&library(prompts), "rev", 3).len(), 3);
+    }
+
+    #[test]
+    fn positions_count_characters_not_bytes() {
+        let library = library(vec![prompt("1", "Café résumé", "Ünïcödé text", None)]);
+        let results = search(&library, "RÉS text", 10);
+        assert_eq!(results[0].title_matches, vec![5, 6, 7]);
+        assert_eq!(results[0].content_matches, vec![8, 9, 10, 11]);
+    }
+}
@@ -0,0 +1,480 @@
+// Global shortcut registration.
+//
+// The toggle shortcut used to be registered once at startup, so changing it
+// in settings did nothing until a restart. It is now owned here and can be
+// swapped at runtime: the new shortcut is registered in place of the old one,
+// and if that fails the old one is put back so the user is never left with no
+// way to open the bar.
+//
+// Prompts can carry their own shortcut. Those are kept in a registry keyed by
+// prompt id and re-synced after every change to the prompt library, so a
+// shortcut follows its prompt however the list is reordered.
+//
+// Every configured shortcut gets a status: registered, in conflict with
+// another Prompt Buddy binding (the toggle shortcut wins, then the chord
+// leader, then prompts in library order), or failed at the OS level, usually
+// because another app already owns it.
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::chords;
+use crate::recorder;
+use crate::prompts::{Prompt, PromptLibrary};
+
+pub const INJECT_PROMPT: &str = "inject-prompt";
+
+// The registered toggle shortcut and the string it was configured as, plus
+// the status of the most recent attempt to register one.
+static TOGGLE_SHORTCUT: Lazy<Mutex<Option<(Shortcut, String)>>> = Lazy::new(|| Mutex::new(None));
+static TOGGLE_STATUS: Lazy<Mutex<Option<ShortcutStatus>>> = Lazy::new(|| Mutex::new(None));
+
+// Registered prompt shortcuts by prompt id, and the outcome of the last sync
+// for every prompt that has a shortcut configured.
+static PROMPT_SHORTCUTS: Lazy<Mutex<HashMap<String, PromptBinding>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static PROMPT_SHORTCUT_STATUS: Lazy<Mutex<Vec<ShortcutStatus>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+struct PromptBinding {
+    shortcut: Shortcut,
+    // The shortcut as the user wrote it.
+    label: String,
+    title: String,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RegistrationState {
+    Registered,
+    // Another Prompt Buddy binding already uses the same keys.
+    Conflict,
+    // The shortcut didn't parse or the OS refused it.
+    Failed,
+}
+
+// What a shortcut is bound to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Binding {
+    Toggle,
+    Leader,
+    Prompt(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum BindingKind {
+    Toggle,
+    Leader,
+    Prompt,
+}
+
+impl Binding {
+    // `prompt_id` is `None` for a prompt that hasn't been created yet.
+    pub fn new(kind: BindingKind, prompt_id: Option<String>) -> Self {
+        match kind {
+            BindingKind::Toggle => Binding::Toggle,
+            BindingKind::Leader => Binding::Leader,
+            BindingKind::Prompt => Binding::Prompt(prompt_id.unwrap_or_default()),
+        }
+    }
+}
+
+// Whether a configured shortcut is actually live, reported back to the UI.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutStatus {
+    pub shortcut: String,
+    pub kind: BindingKind,
+    // Set for prompt shortcuts only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_id: Option<String>,
+    pub state: RegistrationState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    // The binding this one collides with, e.g. `prompt "Refactor"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflicts_with: Option<String>,
+}
+
+impl ShortcutStatus {
+    fn new(shortcut: &str, binding: Binding, state: RegistrationState) -> Self {
+        let (kind, prompt_id) = match binding {
+            Binding::Toggle => (BindingKind::Toggle, None),
+            Binding::Leader => (BindingKind::Leader, None),
+            Binding::Prompt(id) => (BindingKind::Prompt, Some(id)),
+        };
+        Self {
+            shortcut: shortcut.to_string(),
+            kind,
+            prompt_id,
+            state,
+            error: None,
+            conflicts_with: None,
+        }
+    }
+
+    pub fn registered(shortcut: &str, binding: Binding) -> Self {
+        Self::new(shortcut, binding, RegistrationState::Registered)
+    }
+
+    pub fn failed(shortcut: &str, binding: Binding, error: String) -> Self {
+        Self {
+            error: Some(error),
+            ..Self::new(shortcut, binding, RegistrationState::Failed)
+        }
+    }
+
+    pub fn conflict(shortcut: &str, binding: Binding, owner: &str) -> Self {
+        Self {
+            error: Some(format!("'{}' is already used by {}", shortcut, owner)),
+            conflicts_with: Some(owner.to_string()),
+            ..Self::new(shortcut, binding, RegistrationState::Conflict)
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.state == RegistrationState::Registered
+    }
+}
+
+const TOGGLE_OWNER: &str = "the toggle shortcut";
+const LEADER_OWNER: &str = "the chord leader";
+
+pub fn parse(shortcut: &str) -> Result<Shortcut, String> {
+    shortcut
+        .trim()
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", shortcut, e))
+}
+
+fn register_toggle_handler(app: &AppHandle, shortcut: Shortcut, label: String) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app, _shortcut, event| {
+            // Only act on key *press* events so the shortcut truly toggles.
+            if event.state() == ShortcutState::Pressed && !recorder::intercept(&label) {
+                println!("🎯 Global shortcut ({}) pressed!", label);
+                toggle_main_window(app);
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+// Register the toggle shortcut at startup, before any prompt shortcuts.
+pub fn register_toggle(app: &AppHandle, shortcut: &str) -> ShortcutStatus {
+    println!("🎯 Registering main toggle shortcut: {}...", shortcut);
+    let result = parse(shortcut).and_then(|parsed| {
+        register_toggle_handler(app, parsed, shortcut.to_string())?;
+        Ok(parsed)
+    });
+
+    let status = match result {
+        Ok(parsed) => {
+            println!("✅ Main shortcut ({}) registered successfully!", shortcut);
+            *TOGGLE_SHORTCUT.lock().unwrap() = Some((parsed, shortcut.to_string()));
+            ShortcutStatus::registered(shortcut, Binding::Toggle)
+        }
+        Err(e) => {
+            println!("❌ Failed to register main shortcut: {}", e);
+            println!("⚠️  You can still use the app manually, but {} won't work", shortcut);
+            ShortcutStatus::failed(shortcut, Binding::Toggle, e)
+        }
+    };
+    *TOGGLE_STATUS.lock().unwrap() = Some(status.clone());
+    status
+}
+
+// Swap the toggle shortcut for `shortcut` and return how that went. If the
+// new shortcut can't be used the previous one is registered again. The toggle
+// outranks every other binding, as at startup and in `sync_prompts`, so a
+// prompt or the chord leader holding the keys gives them up and is the one
+// reported as the conflict.
+pub fn replace_toggle(app: &AppHandle, shortcut: &str) -> ShortcutStatus {
+    let mut current = TOGGLE_SHORTCUT.lock().unwrap();
+    let previous = current.clone();
+    // Whatever was live before stays the recorded status.
+    let rejected = |status: ShortcutStatus| {
+        match &previous {
+            Some((_, old_label)) => record_toggle(ShortcutStatus::registered(old_label, Binding::Toggle)),
+            None => record_toggle(status.clone()),
+        };
+        status
+    };
+
+    let parsed = match parse(shortcut) {
+        Ok(parsed) => parsed,
+        Err(e) => return rejected(ShortcutStatus::failed(shortcut, Binding::Toggle, e)),
+    };
+
+    if let Some((old, _)) = &previous {
+        if *old == parsed {
+            // Same keys, possibly spelled differently.
+            *current = Some((parsed, shortcut.to_string()));
+            return record_toggle(ShortcutStatus::registered(shortcut, Binding::Toggle));
+        }
+    }
+
+    take_from_other_bindings(app, parsed, shortcut);
+    if let Some((old, old_label)) = &previous {
+        println!("🔄 Replacing toggle shortcut {} with {}", old_label, shortcut);
+        if let Err(e) = app.global_shortcut().unregister(*old) {
+            let error = format!("Failed to unregister shortcut '{}': {}", old_label, e);
+            return rejected(ShortcutStatus::failed(shortcut, Binding::Toggle, error));
+        }
+    } else {
+        println!("🔄 Registering toggle shortcut {}", shortcut);
+    }
+
+    match register_toggle_handler(app, parsed, shortcut.to_string()) {
+        Ok(()) => {
+            println!("✅ Toggle shortcut is now {}", shortcut);
+            *current = Some((parsed, shortcut.to_string()));
+            record_toggle(ShortcutStatus::registered(shortcut, Binding::Toggle))
+        }
+        Err(e) => {
+            println!("❌ Failed to register {}: {}", shortcut, e);
+            let status = ShortcutStatus::failed(shortcut, Binding::Toggle, format!("Failed to register shortcut '{}': {}", shortcut, e));
+            match previous {
+                Some((old, old_label)) => match register_toggle_handler(app, old, old_label.clone()) {
+                    Ok(()) => {
+                        println!("↩️  Restored previous toggle shortcut {}", old_label);
+                        record_toggle(ShortcutStatus::registered(&old_label, Binding::Toggle));
+                    }
+                    Err(e) => {
+                        println!("❌ Failed to restore previous toggle shortcut {}: {}", old_label, e);
+                        *current = None;
+                        record_toggle(ShortcutStatus::failed(&old_label, Binding::Toggle, e));
+                    }
+                },
+                None => {
+                    record_toggle(status.clone());
+                }
+            }
+            status
+        }
+    }
+}
+
+// Release `shortcut` from the chord leader and any prompt so the toggle can
+// have it. A failed toggle registration gets prompts their keys back on the
+// next `sync_prompts`, which `save_settings` runs anyway.
+fn take_from_other_bindings(app: &AppHandle, shortcut: Shortcut, label: &str) {
+    if chords::leader() == Some(shortcut) {
+        chords::yield_leader(app, TOGGLE_OWNER);
+    }
+
+    let mut registered = PROMPT_SHORTCUTS.lock().unwrap();
+    let taken: Vec<String> = registered
+        .iter()
+        .filter(|(_, binding)| binding.shortcut == shortcut)
+        .map(|(prompt_id, _)| prompt_id.clone())
+        .collect();
+    let mut report = PROMPT_SHORTCUT_STATUS.lock().unwrap();
+    for prompt_id in taken {
+        let Some(binding) = registered.remove(&prompt_id) else { continue };
+        println!("🔓 Toggle shortcut {} takes over the shortcut of prompt {}", label, prompt_id);
+        if let Err(e) = app.global_shortcut().unregister(binding.shortcut) {
+            println!("⚠️ Failed to unregister {}: {}", binding.label, e);
+        }
+        let conflict = ShortcutStatus::conflict(&binding.label, Binding::Prompt(prompt_id.clone()), TOGGLE_OWNER);
+        match report.iter_mut().find(|status| status.prompt_id.as_deref() == Some(prompt_id.as_str())) {
+            Some(status) => *status = conflict,
+            None => report.push(conflict),
+        }
+    }
+}
+
+fn record_toggle(status: ShortcutStatus) -> ShortcutStatus {
+    *TOGGLE_STATUS.lock().unwrap() = Some(status.clone());
+    status
+}
+
+// The Prompt Buddy binding other than `except` that has `shortcut`
+// registered, if any.
+pub fn owner_of(shortcut: Shortcut, except: &Binding) -> Option<String> {
+    if *except != Binding::Toggle {
+        if let Some((toggle, _)) = TOGGLE_SHORTCUT.lock().unwrap().as_ref() {
+            if *toggle == shortcut {
+                return Some(TOGGLE_OWNER.to_string());
+            }
+        }
+    }
+    if *except != Binding::Leader && chords::leader() == Some(shortcut) {
+        return Some(LEADER_OWNER.to_string());
+    }
+    PROMPT_SHORTCUTS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(prompt_id, binding)| binding.shortcut == shortcut && *except != Binding::Prompt(prompt_id.to_string()))
+        .map(|(_, binding)| format!("prompt \"{}\"", binding.title))
+}
+
+// The status of the toggle shortcut as last configured.
+pub fn toggle_status() -> ShortcutStatus {
+    TOGGLE_STATUS
+        .lock()
+        .unwrap()
+        .clone()
+        .unwrap_or_else(|| ShortcutStatus::failed("", Binding::Toggle, "No toggle shortcut is registered".to_string()))
+}
+
+fn register_prompt_handler(app: &AppHandle, shortcut: Shortcut, prompt_id: String, label: String) -> Result<(), String> {
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed && !recorder::intercept(&label) {
+                println!("🚀 Prompt shortcut triggered: {} → {}", label, prompt_id);
+                emit_inject(app, &prompt_id);
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+// Ask the bar to inject `prompt_id`, as if its pill had been clicked. Prompt
+// shortcuts and chords fire from whatever app the user is in, so that app
+// becomes the target first; otherwise focus would go back to wherever the bar
+// was last opened.
+pub fn emit_inject(app: &AppHandle, prompt_id: &str) {
+    crate::remember_current_app();
+    if let Some(window) = app.get_webview_window("main") {
+        if let Err(e) = window.emit(INJECT_PROMPT, prompt_id) {
+            println!("❌ Failed to emit {} event: {}", INJECT_PROMPT, e);
+        }
+    }
+}
+
+// Bring the registered prompt shortcuts in line with `library`: shortcuts of
+// deleted prompts, changed bindings and bindings that now conflict are
+// released, new ones registered.
+pub fn sync_prompts(app: &AppHandle, library: &PromptLibrary) {
+    // Decide who owns each key combination before touching any registration.
+    let mut owners: HashMap<Shortcut, String> = HashMap::new();
+    if let Some((toggle, _)) = TOGGLE_SHORTCUT.lock().unwrap().as_ref() {
+        owners.insert(*toggle, TOGGLE_OWNER.to_string());
+    }
+    if let Some(leader) = chords::leader() {
+        owners.insert(leader, LEADER_OWNER.to_string());
+    }
+
+    let mut plan: Vec<(&Prompt, String, Result<Shortcut, ShortcutStatus>)> = Vec::new();
+    for prompt in &library.prompts {
+        let Some(label) = prompt.shortcut.as_deref().map(str::trim).filter(|s| !s.is_empty()) else {
+            continue;
+        };
+        let binding = Binding::Prompt(prompt.id.clone());
+        let outcome = match parse(label) {
+            Err(e) => Err(ShortcutStatus::failed(label, binding, e)),
+            Ok(shortcut) => match owners.get(&shortcut) {
+                Some(owner) => Err(ShortcutStatus::conflict(label, binding, owner)),
+                None => {
+                    owners.insert(shortcut, format!("prompt \"{}\"", prompt.title));
+                    Ok(shortcut)
+                }
+            },
+        };
+        plan.push((prompt, label.to_string(), outcome));
+    }
+
+    let mut registered = PROMPT_SHORTCUTS.lock().unwrap();
+    registered.retain(|prompt_id, binding| {
+        let wanted = plan.iter().find(|(prompt, label, outcome)| {
+            &prompt.id == prompt_id && *label == binding.label && outcome.as_ref().ok() == Some(&binding.shortcut)
+        });
+        match wanted {
+            Some((prompt, _, _)) => {
+                binding.title = prompt.title.clone();
+                true
+            }
+            None => {
+                println!("🔓 Releasing shortcut {} of prompt {}", binding.label, prompt_id);
+                if let Err(e) = app.global_shortcut().unregister(binding.shortcut) {
+                    println!("⚠️ Failed to unregister {}: {}", binding.label, e);
+                }
+                false
+            }
+        }
+    });
+
+    let mut report = Vec::new();
+    for (prompt, label, outcome) in plan {
+        let prompt_id = prompt.id.clone();
+        let status = match outcome {
+            Err(status) => status,
+            Ok(_) if registered.contains_key(&prompt_id) => ShortcutStatus::registered(&label, Binding::Prompt(prompt_id)),
+            Ok(shortcut) => match register_prompt_handler(app, shortcut, prompt_id.clone(), label.clone()) {
+                Ok(()) => {
+                    println!("✅ Registered {} for prompt {}", label, prompt_id);
+                    registered.insert(
+                        prompt_id.clone(),
+                        PromptBinding { shortcut, label: label.clone(), title: prompt.title.clone() },
+                    );
+                    ShortcutStatus::registered(&label, Binding::Prompt(prompt_id))
+                }
+                Err(e) => ShortcutStatus::failed(&label, Binding::Prompt(prompt_id), e),
+            },
+        };
+
+        if let Some(error) = &status.error {
+            println!("❌ Prompt shortcut {} is not active: {}", label, error);
+        }
+        report.push(status);
+    }
+
+    println!("🎯 {} of {} prompt shortcuts active", registered.len(), report.len());
+    *PROMPT_SHORTCUT_STATUS.lock().unwrap() = report;
+}
+
+// Every configured shortcut and whether it is live.
+pub fn status() -> Vec<ShortcutStatus> {
+    let mut statuses = vec![toggle_status()];
+    statuses.extend(chords::leader_status());
+    statuses.extend(PROMPT_SHORTCUT_STATUS.lock().unwrap().iter().cloned());
+    statuses
+}
+
+pub fn toggle_main_window(app: &AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        println!("❌ Could not find main window");
+        return;
+    };
+
+    println!("✅ Found main window");
+    match window.is_visible() {
+        Ok(is_visible) => {
+            println!("👁️  Current window visibility: {}", is_visible);
+            if is_visible {
+                println!("🫥 Hiding prompt picker bar");
+                if let Err(e) = window.hide() {
+                    println!("❌ Failed to hide window: {}", e);
+                }
+            } else {
+                // Before showing the window we record the app
+                // that is currently frontmost so we can switch
+                // back to it later when the user selects a prompt.
+                crate::remember_current_app();
+
+                println!("👁️  Showing prompt picker bar");
+                if let Err(e) = window.show() {
+                    println!("❌ Failed to show window: {}", e);
+                } else {
+                    println!("✅ Window shown successfully");
+                    let _ = window.set_focus();
+                }
+            }
+        }
+        Err(e) => {
+            println!("❌ Failed to get window visibility: {}", e);
+            // Capture frontmost app before stealing focus
+            crate::remember_current_app();
+
+            println!("🔄 Attempting to show window anyway...");
+            if let Err(e) = window.show() {
+                println!("❌ Failed to show window: {}", e);
+            } else {
+                let _ = window.set_focus();
+            }
+        }
+    }
+}
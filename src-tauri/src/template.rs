@@ -0,0 +1,301 @@
+// Template variables expanded in prompts before they are injected.
+//
+// Supported placeholders:
+//
+//   {{clipboard}}            current clipboard text
+//   {{date}} / {{date:FMT}}  local date/time, FMT is a strftime string (default %Y-%m-%d)
+//   {{uuid}}                 a fresh random UUID
+//   {{app}}                  the app the prompt is being injected into
+//   {{input:name}}           a value supplied by the user, optionally with a
+//   {{input:name=default}}   default
+//
+// `\{{` produces a literal `{{`. Anything else between `{{` and `}}` is an
+// error, as is an unterminated `{{`, so typos are caught before typing starts.
+
+use chrono::format::{Item, StrftimeItems};
+use std::collections::HashMap;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Variable {
+    Clipboard,
+    Date(String),
+    Uuid,
+    App,
+    Input { name: String, default: Option<String> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Text(String),
+    Variable(Variable),
+}
+
+fn parse_variable(body: &str) -> Result<Variable, String> {
+    let body = body.trim();
+    let (name, argument) = match body.split_once(':') {
+        Some((name, argument)) => (name.trim(), Some(argument)),
+        None => (body, None),
+    };
+
+    match (name, argument) {
+        ("clipboard", None) => Ok(Variable::Clipboard),
+        ("uuid", None) => Ok(Variable::Uuid),
+        ("app", None) => Ok(Variable::App),
+        ("date", None) => Ok(Variable::Date(DEFAULT_DATE_FORMAT.to_string())),
+        ("date", Some(format)) => {
+            if format.is_empty() || StrftimeItems::new(format).any(|item| item == Item::Error) {
+                return Err(format!("invalid date format '{}'", format));
+            }
+            Ok(Variable::Date(format.to_string()))
+        }
+        ("input", Some(argument)) => {
+            let (name, default) = match argument.split_once('=') {
+                Some((name, default)) => (name.trim(), Some(default.to_string())),
+                None => (argument.trim(), None),
+            };
+            if name.is_empty() {
+                return Err("input variable needs a name, e.g. {{input:topic}}".to_string());
+            }
+            Ok(Variable::Input { name: name.to_string(), default })
+        }
+        ("input", None) => Err("input variable needs a name, e.g. {{input:topic}}".to_string()),
+        ("clipboard" | "uuid" | "app", Some(_)) => Err(format!("'{}' does not take an argument", name)),
+        _ => Err(format!("unknown variable '{}'", body)),
+    }
+}
+
+// Split a prompt into literal text and variables. All problems are collected
+// so the user sees every mistake at once rather than one per attempt.
+pub fn parse(text: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut errors = Vec::new();
+    let mut literal = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        if rest[..start].ends_with('\\') {
+            literal.push_str(&rest[..start - 1]);
+            literal.push_str("{{");
+            rest = &rest[start + 2..];
+            continue;
+        }
+
+        literal.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+        let end = match after_open.find("}}") {
+            Some(end) => end,
+            None => {
+                let offset = text.len() - rest.len() + start;
+                errors.push(format!("unterminated '{{{{' at position {}", offset));
+                rest = "";
+                break;
+            }
+        };
+
+        let body = &after_open[..end];
+        match parse_variable(body) {
+            Ok(variable) => {
+                if !literal.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Variable(variable));
+            }
+            Err(e) => errors.push(format!("{{{{{}}}}}: {}", body, e)),
+        }
+        rest = &after_open[end + 2..];
+    }
+
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        segments.push(Segment::Text(literal));
+    }
+
+    if errors.is_empty() {
+        Ok(segments)
+    } else {
+        Err(format!("Invalid template: {}", errors.join("; ")))
+    }
+}
+
+pub fn has_variables(text: &str) -> bool {
+    text.contains("{{")
+}
+
+// `text` with every `{{` escaped, so it parses to itself. Used to keep prompts
+// written before templates existed typing exactly what they say.
+pub fn escape(text: &str) -> String {
+    text.replace("{{", "\\{{")
+}
+
+// Names of all `{{input:...}}` variables in order of first appearance, with
+// their defaults.
+pub fn input_variables(segments: &[Segment]) -> Vec<(String, Option<String>)> {
+    let mut inputs: Vec<(String, Option<String>)> = Vec::new();
+    for segment in segments {
+        if let Segment::Variable(Variable::Input { name, default }) = segment {
+            match inputs.iter_mut().find(|(existing, _)| existing == name) {
+                // Later occurrences may supply a default the first one didn't.
+                Some((_, existing_default)) => {
+                    if existing_default.is_none() {
+                        *existing_default = default.clone();
+                    }
+                }
+                None => inputs.push((name.clone(), default.clone())),
+            }
+        }
+    }
+    inputs
+}
+
+// Render parsed segments, resolving each variable through `resolve`.
+pub fn render(segments: &[Segment], mut resolve: impl FnMut(&Variable) -> Result<String, String>) -> Result<String, String> {
+    let mut output = String::new();
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => output.push_str(text),
+            Segment::Variable(variable) => output.push_str(&resolve(variable)?),
+        }
+    }
+    Ok(output)
+}
+
+// Expand every placeholder in `text` for injection into `target_app`.
+pub fn expand(
+    app: &AppHandle,
+    text: &str,
+    target_app: Option<&str>,
+    inputs: &HashMap<String, String>,
+) -> Result<String, String> {
+    if !has_variables(text) {
+        return Ok(text.to_string());
+    }
+
+    let segments = parse(text)?;
+    let mut clipboard: Option<String> = None;
+
+    render(&segments, |variable| match variable {
+        Variable::Clipboard => {
+            if clipboard.is_none() {
+                let text = app
+                    .clipboard()
+                    .read_text()
+                    .map_err(|e| format!("Failed to read clipboard for {{{{clipboard}}}}: {}", e))?;
+                clipboard = Some(text);
+            }
+            Ok(clipboard.clone().unwrap_or_default())
+        }
+        Variable::Date(format) => Ok(chrono::Local::now().format(format).to_string()),
+        Variable::Uuid => Ok(uuid::Uuid::new_v4().to_string()),
+        Variable::App => target_app
+            .map(str::to_string)
+            .ok_or_else(|| "No target app recorded for {{app}}".to_string()),
+        Variable::Input { name, default } => inputs
+            .get(name)
+            .cloned()
+            .or_else(|| default.clone())
+            .ok_or_else(|| format!("No value provided for {{{{input:{}}}}}", name)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> Segment {
+        Segment::Text(value.to_string())
+    }
+
+    fn variable(variable: Variable) -> Segment {
+        Segment::Variable(variable)
+    }
+
+    #[test]
+    fn parses_every_variable_kind() {
+        assert_eq!(
+            parse("Fix {{ clipboard }} in {{app}} ({{uuid}})"),
+            Ok(vec![
+                text("Fix "),
+                variable(Variable::Clipboard),
+                text(" in "),
+                variable(Variable::App),
+                text(" ("),
+                variable(Variable::Uuid),
+                text(")"),
+            ])
+        );
+        assert_eq!(parse("{{date}}"), Ok(vec![variable(Variable::Date("%Y-%m-%d".to_string()))]));
+        assert_eq!(parse("{{date:%H:%M}}"), Ok(vec![variable(Variable::Date("%H:%M".to_string()))]));
+        assert_eq!(
+            parse("{{input:topic}}{{input: tone = dry, short }}"),
+            Ok(vec![
+                variable(Variable::Input { name: "topic".to_string(), default: None }),
+                variable(Variable::Input { name: "tone".to_string(), default: Some(" dry, short".to_string()) }),
+            ])
+        );
+    }
+
+    #[test]
+    fn escaped_braces_are_literal() {
+        assert_eq!(parse(r"Use \{{name}} in {{app}}"), Ok(vec![text("Use {{name}} in "), variable(Variable::App)]));
+        assert_eq!(parse("no variables {here}"), Ok(vec![text("no variables {here}")]));
+        assert_eq!(parse(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn reports_each_kind_of_error() {
+        let error = |body: &str| parse(body).unwrap_err();
+        assert_eq!(error("{{name}}"), "Invalid template: {{name}}: unknown variable 'name'");
+        assert_eq!(error("{{date:%Q}}"), "Invalid template: {{date:%Q}}: invalid date format '%Q'");
+        assert_eq!(error("{{date:}}"), "Invalid template: {{date:}}: invalid date format ''");
+        assert_eq!(error("{{uuid:v7}}"), "Invalid template: {{uuid:v7}}: 'uuid' does not take an argument");
+        assert_eq!(
+            error("{{input}}"),
+            "Invalid template: {{input}}: input variable needs a name, e.g. {{input:topic}}"
+        );
+        assert_eq!(
+            error("{{input:=x}}"),
+            "Invalid template: {{input:=x}}: input variable needs a name, e.g. {{input:topic}}"
+        );
+        assert_eq!(error("a {{app"), "Invalid template: unterminated '{{' at position 2");
+    }
+
+    #[test]
+    fn collects_every_error() {
+        assert_eq!(
+            parse("{{foo}} {{app}} {{date:%Q}} {{bar"),
+            Err("Invalid template: {{foo}}: unknown variable 'foo'; {{date:%Q}}: invalid date format '%Q'; unterminated '{{' at position 28".to_string())
+        );
+    }
+
+    #[test]
+    fn escaping_keeps_text_literal() {
+        for original in ["{{foo}}", "{{{x}}}", r"\{{app}}", "map{{", "{{app}} and {{", "plain"] {
+            let segments = parse(&escape(original)).unwrap();
+            assert_eq!(render(&segments, |_| Err("no variables".to_string())), Ok(original.to_string()), "{}", original);
+        }
+    }
+
+    #[test]
+    fn input_variables_merge_defaults() {
+        let segments = parse("{{input:topic}} {{input:tone}} {{input:topic=bugs}} {{input:tone=dry}}").unwrap();
+        assert_eq!(
+            input_variables(&segments),
+            vec![("topic".to_string(), Some("bugs".to_string())), ("tone".to_string(), Some("dry".to_string()))]
+        );
+    }
+
+    #[test]
+    fn render_resolves_each_variable() {
+        let segments = parse("{{app}}: {{input:topic=bugs}}").unwrap();
+        let rendered = render(&segments, |variable| match variable {
+            Variable::App => Ok("Cursor".to_string()),
+            Variable::Input { default, .. } => Ok(default.clone().unwrap_or_default()),
+            other => Err(format!("unexpected {:?}", other)),
+        });
+        assert_eq!(rendered, Ok("Cursor: bugs".to_string()));
+    }
+}
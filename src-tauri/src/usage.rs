@@ -0,0 +1,208 @@
+// Prompt usage tracking and frecency ranking.
+//
+// Every successful injection records which prompt was used, when, and into
+// which app. Prompts are then ranked by frecency: each use contributes a score
+// that halves every `HALF_LIFE_DAYS`, so prompts used often *and* recently
+// float to the top without anyone reordering them by hand.
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::prompts::{self, Prompt};
+
+pub const USAGE_STORE: &str = "usage.json";
+
+// Oldest events are dropped beyond this so the file can't grow forever. At
+// these decay rates an event a few months old contributes next to nothing.
+const MAX_EVENTS: usize = 5000;
+const HALF_LIFE_DAYS: f64 = 7.0;
+// When ranking for a specific app, uses in other apps still count a little so
+// a prompt popular everywhere isn't buried in an app it hasn't been used in.
+const OTHER_APP_WEIGHT: f64 = 0.2;
+
+const MS_PER_DAY: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+
+static USAGE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageEvent {
+    pub prompt_id: String,
+    // Milliseconds since the Unix epoch, like `Date.now()` on the UI side.
+    pub timestamp: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_app: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RankedPrompt {
+    pub prompt: Prompt,
+    pub score: f64,
+    pub use_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<u64>,
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn load_events(app: &AppHandle) -> Result<Vec<UsageEvent>, String> {
+    let store = app
+        .store(USAGE_STORE)
+        .map_err(|e| format!("Failed to load usage store: {}", e))?;
+
+    match store.get("events") {
+        Some(value) => serde_json::from_value(value).map_err(|e| format!("Failed to parse usage events: {}", e)),
+        None => Ok(Vec::new()),
+    }
+}
+
+pub fn record(app: &AppHandle, prompt_id: &str, target_app: Option<String>) -> Result<(), String> {
+    let _guard = USAGE_LOCK.lock().unwrap();
+
+    let mut events = load_events(app)?;
+    events.push(UsageEvent {
+        prompt_id: prompt_id.to_string(),
+        timestamp: now_ms(),
+        target_app,
+    });
+    if events.len() > MAX_EVENTS {
+        let excess = events.len() - MAX_EVENTS;
+        events.drain(..excess);
+    }
+
+    let store = app
+        .store(USAGE_STORE)
+        .map_err(|e| format!("Failed to load usage store: {}", e))?;
+    let value = serde_json::to_value(&events).map_err(|e| format!("Failed to serialize usage events: {}", e))?;
+    store.set("events", value);
+    store.save().map_err(|e| format!("Failed to save usage events: {}", e))?;
+
+    println!("📈 Recorded use of prompt {} ({} events total)", prompt_id, events.len());
+    Ok(())
+}
+
+// Order `prompts` by frecency. Prompts that have never been used keep their
+// relative order at the end of the list.
+pub fn rank(prompts: Vec<Prompt>, events: &[UsageEvent], target_app: Option<&str>, now: u64) -> Vec<RankedPrompt> {
+    let mut ranked: Vec<RankedPrompt> = prompts
+        .into_iter()
+        .map(|prompt| {
+            let mut score = 0.0;
+            let mut use_count = 0;
+            let mut last_used = None;
+
+            for event in events.iter().filter(|e| e.prompt_id == prompt.id) {
+                let age_days = now.saturating_sub(event.timestamp) as f64 / MS_PER_DAY;
+                let weight = match target_app {
+                    Some(app) if event.target_app.as_deref() != Some(app) => OTHER_APP_WEIGHT,
+                    _ => 1.0,
+                };
+                score += weight * 0.5f64.powf(age_days / HALF_LIFE_DAYS);
+                use_count += 1;
+                last_used = last_used.max(Some(event.timestamp));
+            }
+
+            RankedPrompt { prompt, score, use_count, last_used }
+        })
+        .collect();
+
+    // `sort_by` is stable, so ties (including all unused prompts) keep the
+    // library order.
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    ranked
+}
+
+pub fn ranked_prompts(app: &AppHandle, target_app: Option<&str>) -> Result<Vec<RankedPrompt>, String> {
+    let library = prompts::list(app)?;
+    let events = {
+        let _guard = USAGE_LOCK.lock().unwrap();
+        load_events(app)?
+    };
+
+    Ok(rank(library.prompts, &events, target_app, now_ms()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_000 * MS_PER_DAY as u64;
+
+    fn prompt(id: &str) -> Prompt {
+        Prompt {
+            id: id.to_string(),
+            title: id.to_string(),
+            content: String::new(),
+            color: String::new(),
+            folder_id: None,
+            key_macro: None,
+            shortcut: None,
+            mnemonic: None,
+        }
+    }
+
+    fn used(id: &str, days_ago: f64, app: Option<&str>) -> UsageEvent {
+        UsageEvent {
+            prompt_id: id.to_string(),
+            timestamp: NOW - (days_ago * MS_PER_DAY) as u64,
+            target_app: app.map(str::to_string),
+        }
+    }
+
+    fn order(ranked: &[RankedPrompt]) -> Vec<&str> {
+        ranked.iter().map(|r| r.prompt.id.as_str()).collect()
+    }
+
+    #[test]
+    fn recent_uses_outweigh_old_ones() {
+        let prompts = vec![prompt("old"), prompt("recent")];
+        // Three uses five weeks ago are worth less than one today.
+        let events = [used("old", 35.0, None), used("old", 35.0, None), used("old", 35.0, None), used("recent", 0.0, None)];
+        let ranked = rank(prompts, &events, None, NOW);
+        assert_eq!(order(&ranked), vec!["recent", "old"]);
+        assert_eq!(ranked[1].use_count, 3);
+        assert!((ranked[1].score - 3.0 / 32.0).abs() < 1e-9);
+        assert_eq!(ranked[0].last_used, Some(NOW));
+    }
+
+    #[test]
+    fn a_use_halves_every_half_life() {
+        let ranked = rank(vec![prompt("a")], &[used("a", HALF_LIFE_DAYS, None)], None, NOW);
+        assert!((ranked[0].score - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn uses_in_the_target_app_count_most() {
+        let prompts = vec![prompt("elsewhere"), prompt("here")];
+        let events = [
+            used("elsewhere", 0.0, Some("Safari")),
+            used("elsewhere", 0.0, Some("Safari")),
+            used("here", 0.0, Some("Cursor")),
+        ];
+        let ranked = rank(prompts.clone(), &events, Some("Cursor"), NOW);
+        assert_eq!(order(&ranked), vec!["here", "elsewhere"]);
+        assert!((ranked[1].score - 2.0 * OTHER_APP_WEIGHT).abs() < 1e-9);
+
+        // Without a target app every use counts fully.
+        assert_eq!(order(&rank(prompts, &events, None, NOW)), vec!["elsewhere", "here"]);
+    }
+
+    #[test]
+    fn unused_prompts_keep_library_order_at_the_end() {
+        let prompts = vec![prompt("c"), prompt("a"), prompt("used"), prompt("b")];
+        let ranked = rank(prompts, &[used("used", 1.0, None), used("gone", 0.0, None)], None, NOW);
+        assert_eq!(order(&ranked), vec!["used", "c", "a", "b"]);
+        assert_eq!(ranked[1].use_count, 0);
+        assert_eq!(ranked[1].last_used, None);
+    }
+}
@@ -0,0 +1,354 @@
+// Typing on Wayland through the virtual-keyboard protocol (wlroots
+// compositors and KDE). enigo's events go to the X server, which in a Wayland
+// session only XWayland windows listen to.
+//
+// A virtual keyboard brings its own keymap, so rather than look for a layout
+// that has every character of the prompt, we write one: each distinct
+// character gets a key of its own, and the keymap is replaced whenever a long
+// prompt runs out of keys. This is what `wtype` does. Key macros go the same
+// way, with modifiers sent as a mask rather than as key presses.
+
+use std::io::Write;
+use std::os::fd::AsFd;
+use std::time::Instant;
+
+use enigo::Key;
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::wl_keyboard::KeymapFormat;
+use wayland_client::protocol::wl_registry::WlRegistry;
+use wayland_client::protocol::wl_seat::WlSeat;
+use wayland_client::{Connection, Dispatch, EventQueue, QueueHandle};
+
+use protocol::zwp_virtual_keyboard_manager_v1::ZwpVirtualKeyboardManagerV1;
+use protocol::zwp_virtual_keyboard_v1::ZwpVirtualKeyboardV1;
+
+#[allow(dead_code, non_camel_case_types, non_upper_case_globals, unused_imports, clippy::all)]
+mod protocol {
+    use wayland_client;
+    use wayland_client::protocol::*;
+
+    pub mod __interfaces {
+        use wayland_client::protocol::__interfaces::*;
+        wayland_scanner::generate_interfaces!("protocols/virtual-keyboard-unstable-v1.xml");
+    }
+    use self::__interfaces::*;
+
+    wayland_scanner::generate_client_code!("protocols/virtual-keyboard-unstable-v1.xml");
+}
+
+// Evdev codes start at 1 (0 is reserved); xkb keycodes are 8 higher. Staying
+// below 256 keeps the keymap valid for XWayland clients too.
+const FIRST_KEY: u32 = 1;
+const MAX_KEYS: usize = 240;
+
+// Real modifier masks in the standard xkb "complete" types.
+const SHIFT: u32 = 1 << 0;
+const CONTROL: u32 = 1 << 2;
+const ALT: u32 = 1 << 3;
+const SUPER: u32 = 1 << 6;
+
+const KEY_RELEASED: u32 = 0;
+const KEY_PRESSED: u32 = 1;
+
+const XK_BACKSPACE: u32 = 0xff08;
+const XK_TAB: u32 = 0xff09;
+const XK_RETURN: u32 = 0xff0d;
+const XK_ESCAPE: u32 = 0xff1b;
+const XK_HOME: u32 = 0xff50;
+const XK_LEFT: u32 = 0xff51;
+const XK_UP: u32 = 0xff52;
+const XK_RIGHT: u32 = 0xff53;
+const XK_DOWN: u32 = 0xff54;
+const XK_PAGE_UP: u32 = 0xff55;
+const XK_PAGE_DOWN: u32 = 0xff56;
+const XK_END: u32 = 0xff57;
+const XK_F1: u32 = 0xffbe;
+const XK_DELETE: u32 = 0xffff;
+
+// Latin-1 keysyms are the code points themselves; everything else lives in the
+// Unicode keysym range.
+fn keysym(c: char) -> u32 {
+    match c {
+        '\n' => XK_RETURN,
+        '\t' => XK_TAB,
+        ' '..='~' | '\u{a0}'..='\u{ff}' => c as u32,
+        _ => 0x0100_0000 + c as u32,
+    }
+}
+
+// The keysym for a key a key macro can press.
+fn key_keysym(key: Key) -> Option<u32> {
+    let keysym = match key {
+        Key::Return => XK_RETURN,
+        Key::Tab => XK_TAB,
+        Key::Escape => XK_ESCAPE,
+        Key::Backspace => XK_BACKSPACE,
+        Key::Delete => XK_DELETE,
+        Key::Space => keysym(' '),
+        Key::UpArrow => XK_UP,
+        Key::DownArrow => XK_DOWN,
+        Key::LeftArrow => XK_LEFT,
+        Key::RightArrow => XK_RIGHT,
+        Key::Home => XK_HOME,
+        Key::End => XK_END,
+        Key::PageUp => XK_PAGE_UP,
+        Key::PageDown => XK_PAGE_DOWN,
+        Key::F1 => XK_F1,
+        Key::F2 => XK_F1 + 1,
+        Key::F3 => XK_F1 + 2,
+        Key::F4 => XK_F1 + 3,
+        Key::F5 => XK_F1 + 4,
+        Key::F6 => XK_F1 + 5,
+        Key::F7 => XK_F1 + 6,
+        Key::F8 => XK_F1 + 7,
+        Key::F9 => XK_F1 + 8,
+        Key::F10 => XK_F1 + 9,
+        Key::F11 => XK_F1 + 10,
+        Key::F12 => XK_F1 + 11,
+        Key::Unicode(c) => keysym(c),
+        _ => return None,
+    };
+    Some(keysym)
+}
+
+fn modifier_mask(key: Key) -> Option<u32> {
+    match key {
+        Key::Shift => Some(SHIFT),
+        Key::Control => Some(CONTROL),
+        Key::Alt => Some(ALT),
+        Key::Meta => Some(SUPER),
+        _ => None,
+    }
+}
+
+// A run of text that fits in one keymap: the keysyms to load and the keysyms
+// to press, in order.
+struct Chunk {
+    keys: Vec<u32>,
+    presses: Vec<u32>,
+}
+
+fn chunks(text: &str) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    let mut chars = text.chars().peekable();
+    while chars.peek().is_some() {
+        // Take characters until the keymap is full.
+        let mut chunk = Chunk { keys: Vec::new(), presses: Vec::new() };
+        while let Some(c) = chars.peek().copied() {
+            let keysym = keysym(c);
+            if !chunk.keys.contains(&keysym) {
+                if chunk.keys.len() == MAX_KEYS {
+                    break;
+                }
+                chunk.keys.push(keysym);
+            }
+            chunk.presses.push(keysym);
+            chars.next();
+        }
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+fn keymap(keysyms: &[u32]) -> String {
+    let mut keycodes = String::new();
+    let mut symbols = String::new();
+    for (i, keysym) in keysyms.iter().enumerate() {
+        let code = FIRST_KEY + i as u32 + 8;
+        keycodes.push_str(&format!("        <K{}> = {};\n", code, code));
+        symbols.push_str(&format!("        key <K{}> {{ [ {:#x} ] }};\n", code, keysym));
+    }
+    format!(
+        "xkb_keymap {{\n    xkb_keycodes \"prompt-buddy\" {{\n        minimum = 8;\n        maximum = 255;\n{}    }};\n    xkb_types \"prompt-buddy\" {{ include \"complete\" }};\n    xkb_compatibility \"prompt-buddy\" {{ include \"complete\" }};\n    xkb_symbols \"prompt-buddy\" {{\n{}    }};\n}};\n",
+        keycodes, symbols
+    )
+}
+
+struct State;
+
+impl Dispatch<WlRegistry, GlobalListContents> for State {
+    fn event(_: &mut Self, _: &WlRegistry, _: <WlRegistry as wayland_client::Proxy>::Event, _: &GlobalListContents, _: &Connection, _: &QueueHandle<Self>) {}
+}
+
+impl Dispatch<WlSeat, ()> for State {
+    fn event(_: &mut Self, _: &WlSeat, _: <WlSeat as wayland_client::Proxy>::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {}
+}
+
+impl Dispatch<ZwpVirtualKeyboardManagerV1, ()> for State {
+    fn event(_: &mut Self, _: &ZwpVirtualKeyboardManagerV1, _: <ZwpVirtualKeyboardManagerV1 as wayland_client::Proxy>::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {}
+}
+
+impl Dispatch<ZwpVirtualKeyboardV1, ()> for State {
+    fn event(_: &mut Self, _: &ZwpVirtualKeyboardV1, _: <ZwpVirtualKeyboardV1 as wayland_client::Proxy>::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {}
+}
+
+pub struct VirtualKeyboard {
+    queue: EventQueue<State>,
+    keyboard: ZwpVirtualKeyboardV1,
+    // Keysyms of the current keymap, by key.
+    keys: Vec<u32>,
+    started: Instant,
+}
+
+// Whether the compositor offers a virtual keyboard, without creating one.
+pub fn is_available() -> bool {
+    let Ok(conn) = Connection::connect_to_env() else {
+        return false;
+    };
+    let Ok((globals, _)) = registry_queue_init::<State>(&conn) else {
+        return false;
+    };
+    let names: Vec<String> = globals.contents().with_list(|list| list.iter().map(|global| global.interface.clone()).collect());
+    ["wl_seat", "zwp_virtual_keyboard_manager_v1"]
+        .iter()
+        .all(|interface| names.iter().any(|name| name == interface))
+}
+
+impl VirtualKeyboard {
+    pub fn connect() -> Option<Self> {
+        let conn = Connection::connect_to_env().ok()?;
+        let (globals, mut queue) = registry_queue_init::<State>(&conn).ok()?;
+        let qh = queue.handle();
+        let seat = globals.bind::<WlSeat, _, _>(&qh, 1..=1, ()).ok()?;
+        let Ok(manager) = globals.bind::<ZwpVirtualKeyboardManagerV1, _, _>(&qh, 1..=1, ()) else {
+            println!("⚠️ The compositor doesn't support virtual-keyboard");
+            return None;
+        };
+        let keyboard = manager.create_virtual_keyboard(&seat, &qh, ());
+        // Surfaces a refusal (an `unauthorized` protocol error) here.
+        queue.roundtrip(&mut State).ok()?;
+        Some(Self {
+            queue,
+            keyboard,
+            keys: Vec::new(),
+            started: Instant::now(),
+        })
+    }
+
+    fn load_keymap(&mut self, keysyms: Vec<u32>) -> Result<(), String> {
+        let mut keymap = keymap(&keysyms).into_bytes();
+        keymap.push(0);
+
+        let mut file = tempfile::tempfile().map_err(|e| format!("❌ Failed to create keymap file: {}", e))?;
+        file.write_all(&keymap).map_err(|e| format!("❌ Failed to write keymap: {}", e))?;
+        self.keyboard.keymap(KeymapFormat::XkbV1 as u32, file.as_fd(), keymap.len() as u32);
+        // The compositor must have the keymap before the first key of it.
+        self.queue
+            .roundtrip(&mut State)
+            .map_err(|e| format!("❌ Failed to upload keymap: {}", e))?;
+        self.keys = keysyms;
+        Ok(())
+    }
+
+    fn press(&mut self, keysym: u32, modifiers: u32) {
+        let Some(index) = self.keys.iter().position(|key| *key == keysym) else {
+            return;
+        };
+        let key = FIRST_KEY + index as u32;
+        let time = self.started.elapsed().as_millis() as u32;
+        if modifiers != 0 {
+            self.keyboard.modifiers(modifiers, 0, 0, 0);
+        }
+        self.keyboard.key(time, key, KEY_PRESSED);
+        self.keyboard.key(time, key, KEY_RELEASED);
+        if modifiers != 0 {
+            self.keyboard.modifiers(0, 0, 0, 0);
+        }
+    }
+
+    // Type `text`, pressing Shift+Enter for line breaks if `shift_enter`.
+    pub fn type_text(&mut self, text: &str, shift_enter: bool) -> Result<(), String> {
+        for Chunk { keys, presses } in chunks(text) {
+            self.load_keymap(keys)?;
+            for keysym in presses {
+                let modifiers = if shift_enter && keysym == XK_RETURN { SHIFT } else { 0 };
+                self.press(keysym, modifiers);
+            }
+        }
+        self.queue
+            .roundtrip(&mut State)
+            .map(|_| ())
+            .map_err(|e| format!("❌ Failed to inject text: {}", e))
+    }
+
+    // Press `key` with `modifiers` held, for key macros.
+    pub fn press_chord(&mut self, modifiers: &[Key], key: Key) -> Result<(), String> {
+        let keysym = key_keysym(key).ok_or_else(|| format!("❌ Key macro can't press {:?} on the virtual keyboard", key))?;
+        let mut mask = 0;
+        for modifier in modifiers {
+            mask |= modifier_mask(*modifier).ok_or_else(|| format!("❌ Key macro can't hold {:?} on the virtual keyboard", modifier))?;
+        }
+        self.load_keymap(vec![keysym])?;
+        self.press(keysym, mask);
+        self.queue
+            .roundtrip(&mut State)
+            .map(|_| ())
+            .map_err(|e| format!("❌ Key macro failed to press {:?}: {}", key, e))
+    }
+
+    // Ctrl+V.
+    pub fn paste_chord(&mut self) -> Result<(), String> {
+        let v = keysym('v');
+        self.load_keymap(vec![v])?;
+        self.press(v, CONTROL);
+        self.queue
+            .roundtrip(&mut State)
+            .map(|_| ())
+            .map_err(|e| format!("❌ Failed to send paste shortcut: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_ascii_characters_get_unicode_keysyms() {
+        assert_eq!(keysym('a'), 0x61);
+        assert_eq!(keysym('é'), 0xe9);
+        assert_eq!(keysym('€'), 0x0100_20ac);
+        assert_eq!(keysym('😀'), 0x0101_f600);
+
+        let keymap = keymap(&[keysym('€'), keysym('é')]);
+        assert!(keymap.contains("key <K9> { [ 0x10020ac ] };"));
+        assert!(keymap.contains("key <K10> { [ 0xe9 ] };"));
+        assert!(keymap.contains("<K10> = 10;"));
+    }
+
+    #[test]
+    fn newlines_and_tabs_are_keys() {
+        let chunks = chunks("a\nb\ta\n");
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].keys, vec![0x61, XK_RETURN, 0x62, XK_TAB]);
+        assert_eq!(chunks[0].presses, vec![0x61, XK_RETURN, 0x62, XK_TAB, 0x61, XK_RETURN]);
+    }
+
+    #[test]
+    fn long_prompts_are_split_when_the_keymap_is_full() {
+        let text: String = (0..300u32).filter_map(|i| char::from_u32(0x4e00 + i)).chain("伫伫".chars()).collect();
+        let chunks = chunks(&text);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].keys.len(), MAX_KEYS);
+        assert_eq!(chunks[1].keys.len(), 60);
+        // Characters already in the current keymap don't take a key.
+        assert_eq!(chunks[1].presses.len(), 62);
+        assert!(chunks.iter().all(|chunk| chunk.presses.iter().all(|keysym| chunk.keys.contains(keysym))));
+        let typed: Vec<u32> = chunks.iter().flat_map(|chunk| chunk.presses.clone()).collect();
+        assert_eq!(typed, text.chars().map(keysym).collect::<Vec<_>>());
+
+        // Every keycode stays in range for XWayland.
+        let keymap = keymap(&chunks[0].keys);
+        assert!(keymap.contains(&format!("<K{}>", FIRST_KEY + 8 + MAX_KEYS as u32 - 1)));
+        assert!(FIRST_KEY + 8 + MAX_KEYS as u32 - 1 <= 255);
+    }
+
+    #[test]
+    fn key_macro_keys_have_keysyms() {
+        assert_eq!(key_keysym(Key::Return), Some(XK_RETURN));
+        assert_eq!(key_keysym(Key::F12), Some(0xffc9));
+        assert_eq!(key_keysym(Key::Unicode('a')), Some(0x61));
+        assert_eq!(modifier_mask(Key::Control), Some(CONTROL));
+        assert_eq!(modifier_mask(Key::Unicode('a')), None);
+    }
+}
@@ -0,0 +1,154 @@
+// Platform window management behind one trait.
+//
+// Finding the frontmost app, listing windows, activating an app and seeing
+// through wrapper processes like Electron used to be `osascript` calls spread
+// over `lib.rs` behind `cfg(target_os)`. They now live in one backend per
+// platform, and the focus logic in `focus` only talks to `WindowSystem`, so
+// tests can run it against the in-memory mock.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+#[cfg(target_os = "linux")]
+mod desktop;
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(test)]
+pub mod mock;
+#[cfg(target_os = "linux")]
+mod process_tree;
+#[cfg(target_os = "linux")]
+mod wayland;
+#[cfg(target_os = "linux")]
+mod x11;
+
+// One window, or one app on platforms that only expose apps.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowInfo {
+    // The process or app name as the platform reports it, e.g. "Electron".
+    pub app_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    // The platform's own id for the window where it has a stable one (X11).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window_id: Option<u64>,
+}
+
+impl WindowInfo {
+    pub fn new(app_name: &str) -> Self {
+        Self {
+            app_name: app_name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    // Whether this describes one window rather than just an app.
+    pub fn is_specific(&self) -> bool {
+        self.window_id.is_some() || self.title.is_some()
+    }
+
+    // Whether both describe the same window: by id where the platform has
+    // one, otherwise by app, process and title, which is the best macOS and
+    // Wayland offer.
+    pub fn same_window(&self, other: &WindowInfo) -> bool {
+        match (self.window_id, other.window_id) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.app_name == other.app_name && self.pid == other.pid && self.title == other.title,
+        }
+    }
+}
+
+pub trait WindowSystem: Send + Sync {
+    // Whether the backend can reach the window system in this session.
+    fn is_available(&self) -> bool;
+
+    // The window that currently has focus.
+    fn frontmost_window(&self) -> Option<WindowInfo>;
+
+    // Visible windows, most recently used first where the platform knows.
+    fn list_windows(&self) -> Vec<WindowInfo>;
+
+    // Bring `app_name` to the front. Returns whether that worked.
+    fn activate_app(&self, app_name: &str) -> bool;
+
+    // Bring the window `window` describes to the front, as captured by
+    // `frontmost_window` (with its app name possibly resolved). Returns false
+    // if the window can't be found, e.g. because it was closed.
+    fn activate_window(&self, window: &WindowInfo) -> bool;
+
+    fn is_running(&self, app_name: &str) -> bool;
+
+    // The app a wrapper process (see `wrappers`) is actually running.
+    fn resolve_wrapper(&self, window: &WindowInfo) -> Option<String>;
+}
+
+// Platforms without a backend: nothing is ever frontmost and nothing can be
+// activated, which is what the old stubs did.
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+struct UnsupportedWindowSystem;
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+impl WindowSystem for UnsupportedWindowSystem {
+    fn is_available(&self) -> bool {
+        false
+    }
+
+    fn frontmost_window(&self) -> Option<WindowInfo> {
+        None
+    }
+
+    fn list_windows(&self) -> Vec<WindowInfo> {
+        Vec::new()
+    }
+
+    fn activate_app(&self, _app_name: &str) -> bool {
+        false
+    }
+
+    fn activate_window(&self, _window: &WindowInfo) -> bool {
+        false
+    }
+
+    // We can't tell, so assume the app is there.
+    fn is_running(&self, _app_name: &str) -> bool {
+        true
+    }
+
+    fn resolve_wrapper(&self, _window: &WindowInfo) -> Option<String> {
+        None
+    }
+}
+
+// Wayland sessions set `WAYLAND_DISPLAY`; X11 apps there run under XWayland
+// and can't see or focus native windows.
+#[cfg(target_os = "linux")]
+pub fn is_wayland_session() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some_and(|display| !display.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn platform() -> Box<dyn WindowSystem> {
+    Box::new(macos::MacWindowSystem)
+}
+
+#[cfg(target_os = "linux")]
+fn platform() -> Box<dyn WindowSystem> {
+    Box::new(linux::LinuxWindowSystem)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn platform() -> Box<dyn WindowSystem> {
+    Box::new(UnsupportedWindowSystem)
+}
+
+static CURRENT: Lazy<Box<dyn WindowSystem>> = Lazy::new(platform);
+
+// The backend for the platform we're running on.
+pub fn current() -> &'static dyn WindowSystem {
+    CURRENT.as_ref()
+}
@@ -0,0 +1,243 @@
+// Naming apps from their `.desktop` files, which is where Linux keeps an app's
+// display name ("Visual Studio Code") next to the ids it goes by: the file
+// name ("code.desktop", "com.visualstudio.code.desktop"), the window class
+// (`StartupWMClass`) and the executable (`Exec`).
+//
+// Names are looked up on every focus change, so the parsed entries are kept
+// until one of the directories changes: installing or removing an app adds or
+// removes a file, which bumps its directory's modification time.
+
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+struct DesktopEntry {
+    // The file name without `.desktop`.
+    id: String,
+    name: String,
+    wm_class: Option<String>,
+    // The program `Exec` runs, past any `env VAR=value` prefix.
+    program: Option<String>,
+}
+
+impl DesktopEntry {
+    fn parse(id: &str, text: &str) -> Option<Self> {
+        let mut in_entry = false;
+        let (mut name, mut wm_class, mut program) = (None, None, None);
+        for line in text.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_entry = line == "[Desktop Entry]";
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=').filter(|_| in_entry) else {
+                continue;
+            };
+            match key.trim() {
+                "Name" => name = Some(value.trim().to_string()),
+                "StartupWMClass" => wm_class = Some(value.trim().to_string()),
+                "Exec" => {
+                    program = value
+                        .split_whitespace()
+                        .map(|part| part.trim_matches('"'))
+                        .find(|part| *part != "env" && !part.contains('='))
+                        .map(str::to_string)
+                }
+                // Handlers and helpers that aren't apps of their own.
+                "NoDisplay" | "Hidden" if value.trim() == "true" => return None,
+                "Type" if value.trim() != "Application" => return None,
+                _ => {}
+            }
+        }
+        Some(Self {
+            id: id.to_string(),
+            name: name?,
+            wm_class,
+            program,
+        })
+    }
+
+    // How well the entry matches `identity`, if at all: the file name is the
+    // app's canonical id, the window class and executable are good hints, and
+    // the display name itself is a last resort (AppImage integrations name
+    // their files after a hash).
+    fn score(&self, identity: &str) -> Option<u8> {
+        let is = |value: &str| value.eq_ignore_ascii_case(identity);
+        if is(&self.id) {
+            return Some(3);
+        }
+        if self.wm_class.as_deref().is_some_and(is) {
+            return Some(2);
+        }
+        if let Some(program) = &self.program {
+            let file = program.rsplit('/').next().unwrap_or(program);
+            let dir = format!("/{}/", identity.to_lowercase());
+            if is(file) || program.to_lowercase().contains(&dir) {
+                return Some(1);
+            }
+        }
+        is(&self.name).then_some(0)
+    }
+}
+
+// `applications` directories, the user's first, per the XDG base directory
+// spec, plus Flatpak's exports in case the session doesn't list them.
+fn application_dirs() -> Vec<PathBuf> {
+    let home = std::env::var("HOME").unwrap_or_default();
+    let data_home = std::env::var("XDG_DATA_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .unwrap_or_else(|| format!("{}/.local/share", home));
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+    let mut dirs = vec![data_home.clone()];
+    dirs.extend(data_dirs.split(':').map(str::to_string));
+    dirs.push(format!("{}/flatpak/exports/share", data_home));
+    dirs.push("/var/lib/flatpak/exports/share".to_string());
+
+    let mut seen = HashSet::new();
+    dirs.into_iter()
+        .filter(|dir| !dir.is_empty() && seen.insert(dir.clone()))
+        .map(|dir| PathBuf::from(dir).join("applications"))
+        .collect()
+}
+
+// The directories an entry list was read from, with their modification times
+// then (`None` for a missing directory).
+type Stamps = Vec<(PathBuf, Option<SystemTime>)>;
+
+struct Cache {
+    stamps: Stamps,
+    entries: Arc<Vec<DesktopEntry>>,
+}
+
+static CACHE: Lazy<Mutex<Option<Cache>>> = Lazy::new(|| Mutex::new(None));
+
+fn stamps(dirs: Vec<PathBuf>) -> Stamps {
+    dirs.into_iter()
+        .map(|dir| {
+            let modified = fs::metadata(&dir).and_then(|meta| meta.modified()).ok();
+            (dir, modified)
+        })
+        .collect()
+}
+
+fn entries(dirs: Vec<PathBuf>) -> Arc<Vec<DesktopEntry>> {
+    let stamps = stamps(dirs);
+    let mut cache = CACHE.lock().unwrap();
+    if let Some(cached) = cache.as_ref().filter(|cached| cached.stamps == stamps) {
+        return cached.entries.clone();
+    }
+    let entries = Arc::new(read_entries(&stamps));
+    *cache = Some(Cache { stamps, entries: entries.clone() });
+    entries
+}
+
+fn read_entries(stamps: &Stamps) -> Vec<DesktopEntry> {
+    let mut entries = Vec::new();
+    for (dir, _) in stamps {
+        let Ok(files) = fs::read_dir(dir) else { continue };
+        for file in files.flatten() {
+            let path = file.path();
+            let Some(id) = path.file_name().and_then(|name| name.to_str()?.strip_suffix(".desktop")) else {
+                continue;
+            };
+            if let Some(entry) = fs::read_to_string(&path).ok().and_then(|text| DesktopEntry::parse(id, &text)) {
+                entries.push(entry);
+            }
+        }
+    }
+    entries
+}
+
+// The display name of the app `identity` refers to. On ties the user's own
+// entries, which come first, win.
+pub fn app_name(identity: &str) -> Option<String> {
+    let entries = entries(application_dirs());
+    let mut best: Option<(u8, &DesktopEntry)> = None;
+    for entry in entries.iter() {
+        if let Some(score) = entry.score(identity) {
+            if best.is_none_or(|(best_score, _)| score > best_score) {
+                best = Some((score, entry));
+            }
+        }
+    }
+    let (_, entry) = best?;
+    println!("🔍 {} is {} ({}.desktop)", identity, entry.name, entry.id);
+    Some(entry.name.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = "[Desktop Entry]
+Name=Visual Studio Code
+Exec=env FOO=1 /usr/share/code/code %F
+StartupWMClass=Code
+Type=Application
+
+[Desktop Action new-empty-window]
+Name=New Empty Window
+Exec=/usr/share/code/code --new-window %F
+";
+
+    #[test]
+    fn parses_the_desktop_entry_group() {
+        let entry = DesktopEntry::parse("code", CODE).unwrap();
+        assert_eq!(entry.name, "Visual Studio Code");
+        assert_eq!(entry.wm_class.as_deref(), Some("Code"));
+        assert_eq!(entry.program.as_deref(), Some("/usr/share/code/code"));
+
+        let entry = DesktopEntry::parse("code", "[Desktop Entry]\nName=Code\nExec=env FOO=1 code %F\n").unwrap();
+        assert_eq!(entry.program.as_deref(), Some("code"));
+    }
+
+    #[test]
+    fn skips_hidden_entries_and_non_applications() {
+        assert!(DesktopEntry::parse("code-url-handler", &CODE.replace("Type=Application", "Type=Application\nNoDisplay=true")).is_none());
+        assert!(DesktopEntry::parse("code", &CODE.replace("Type=Application", "Type=Link")).is_none());
+        assert!(DesktopEntry::parse("nameless", "[Desktop Entry]\nExec=true\n").is_none());
+    }
+
+    #[test]
+    fn scores_ids_above_classes_above_programs_above_names() {
+        let entry = DesktopEntry::parse("com.visualstudio.code", CODE).unwrap();
+        assert_eq!(entry.score("com.visualstudio.code"), Some(3));
+        assert_eq!(entry.score("code"), Some(2));
+        assert_eq!(entry.score("visual studio code"), Some(0));
+        assert_eq!(entry.score("cursor"), None);
+
+        let obsidian = DesktopEntry::parse("appimagekit_0f3c", "[Desktop Entry]\nName=Obsidian\nExec=/usr/lib/obsidian/obsidian\n").unwrap();
+        assert_eq!(obsidian.score("obsidian"), Some(1));
+    }
+
+    #[test]
+    fn rereads_entries_when_a_directory_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |id: &str, name: &str| {
+            fs::write(dir.path().join(format!("{}.desktop", id)), format!("[Desktop Entry]\nName={}\n", name)).unwrap();
+        };
+        let touch = |secs: u64| {
+            let time = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(secs);
+            fs::File::open(dir.path()).unwrap().set_modified(time).unwrap();
+        };
+
+        write("obsidian", "Obsidian");
+        touch(1_000);
+        assert_eq!(entries(vec![dir.path().to_path_buf()]).len(), 1);
+
+        // Same modification time: the cached list, even though a file changed.
+        write("cursor", "Cursor");
+        touch(1_000);
+        assert_eq!(entries(vec![dir.path().to_path_buf()]).len(), 1);
+
+        touch(2_000);
+        assert_eq!(entries(vec![dir.path().to_path_buf()]).len(), 2);
+    }
+}
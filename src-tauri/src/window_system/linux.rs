@@ -0,0 +1,176 @@
+// Linux backend.
+//
+// Under X11 windows are tracked and activated through EWMH, see `x11`; in a
+// Wayland session through the compositor's foreign-toplevel protocol, see
+// `wayland`. Without either nothing is frontmost and activation fails, as
+// before. Whether an app is running is answered from `/proc` either way.
+//
+// Wrapper processes are resolved through `process_tree` and named through
+// `desktop`; activation and `is_running` accept the resolved name too.
+
+use std::fs;
+
+use x11rb::protocol::xproto::Window;
+
+use super::wayland::Wayland;
+use super::x11::X11;
+use super::{desktop, is_wayland_session, process_tree, WindowInfo, WindowSystem};
+use crate::routing::{self, AppContext};
+use crate::wrappers;
+
+pub struct LinuxWindowSystem;
+
+// Names of all running processes, from `/proc/<pid>/comm`.
+fn process_names() -> Vec<String> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().chars().all(|c| c.is_ascii_digit()))
+        .filter_map(|entry| fs::read_to_string(entry.path().join("comm")).ok())
+        .map(|name| name.trim().to_string())
+        .collect()
+}
+
+impl LinuxWindowSystem {
+    // Whether `window` belongs to `app_name`, by window class or by the app a
+    // wrapper window resolves to.
+    fn is_app(&self, window: &WindowInfo, app_name: &str) -> bool {
+        window.app_name.eq_ignore_ascii_case(app_name)
+            || self.resolve_wrapper(window).is_some_and(|name| name.eq_ignore_ascii_case(app_name))
+    }
+}
+
+impl WindowSystem for LinuxWindowSystem {
+    fn is_available(&self) -> bool {
+        if is_wayland_session() {
+            Wayland::connect().is_some()
+        } else {
+            X11::connect().is_some()
+        }
+    }
+
+    fn frontmost_window(&self) -> Option<WindowInfo> {
+        if is_wayland_session() {
+            return Wayland::connect()?.active_window();
+        }
+        let x11 = X11::connect()?;
+        let window = x11.active_window()?;
+        x11.window_info(window)
+    }
+
+    fn list_windows(&self) -> Vec<WindowInfo> {
+        if is_wayland_session() {
+            return Wayland::connect().map(|wayland| wayland.windows()).unwrap_or_default();
+        }
+        let Some(x11) = X11::connect() else {
+            return Vec::new();
+        };
+        x11.client_windows()
+            .into_iter()
+            .filter_map(|window| x11.window_info(window))
+            .collect()
+    }
+
+    // Activates the most recently used window of the app.
+    fn activate_app(&self, app_name: &str) -> bool {
+        if is_wayland_session() {
+            let Some(mut wayland) = Wayland::connect() else {
+                return false;
+            };
+            let window = wayland.windows().into_iter().find(|window| self.is_app(window, app_name));
+            return match window {
+                Some(window) => wayland.activate(&window),
+                None => {
+                    println!("⚠️ No Wayland toplevel found for {}", app_name);
+                    false
+                }
+            };
+        }
+        let Some(x11) = X11::connect() else {
+            return false;
+        };
+        let window = x11.client_windows().into_iter().find(|window| {
+            x11.window_info(*window).is_some_and(|info| self.is_app(&info, app_name))
+        });
+        match window {
+            Some(window) => {
+                println!("🚀 Activating X11 window {:#x} of {}", window, app_name);
+                x11.activate(window)
+            }
+            None => {
+                println!("⚠️ No X11 window found for {}", app_name);
+                false
+            }
+        }
+    }
+
+    // The captured app name may be resolved from a wrapper, so the window is
+    // matched by id under X11 and by app and title under Wayland.
+    fn activate_window(&self, window: &WindowInfo) -> bool {
+        if is_wayland_session() {
+            let Some(mut wayland) = Wayland::connect() else {
+                return false;
+            };
+            let Some(title) = window.title.as_deref() else {
+                return false;
+            };
+            let found = wayland
+                .windows()
+                .into_iter()
+                .find(|candidate| candidate.title.as_deref() == Some(title) && self.is_app(candidate, &window.app_name));
+            return match found {
+                Some(found) => wayland.activate(&found),
+                None => {
+                    println!("⚠️ The {} window \"{}\" is gone", window.app_name, title);
+                    false
+                }
+            };
+        }
+        let Some(x11) = X11::connect() else {
+            return false;
+        };
+        let Some(id) = window.window_id.and_then(|id| Window::try_from(id).ok()) else {
+            return false;
+        };
+        if !x11.client_windows().contains(&id) {
+            println!("⚠️ X11 window {:#x} of {} is gone", id, window.app_name);
+            return false;
+        }
+        println!("🚀 Activating X11 window {:#x} of {}", id, window.app_name);
+        x11.activate(id)
+    }
+
+    fn is_running(&self, app_name: &str) -> bool {
+        process_names().iter().any(|name| name.eq_ignore_ascii_case(app_name))
+            || self.list_windows().iter().any(|w| self.is_app(w, app_name))
+    }
+
+    // Wayland windows come without a pid, so only their `app_id` can be
+    // looked up as a desktop entry.
+    fn resolve_wrapper(&self, window: &WindowInfo) -> Option<String> {
+        let wrapper = wrappers::find(&window.app_name)?;
+        let identity = match window.pid {
+            Some(pid) => process_tree::identity(pid, wrapper),
+            None => None,
+        };
+        let identity = match identity {
+            Some(identity) => identity,
+            None if wrapper.opaque => return None,
+            None => window.app_name.clone(),
+        };
+        let app_name = desktop::app_name(&identity).unwrap_or(identity);
+
+        // Routing rules get the last say, as for Electron bundles on macOS.
+        let exe = window.pid.and_then(|pid| fs::read_link(format!("/proc/{}/exe", pid)).ok());
+        let context = AppContext {
+            app_name,
+            bundle_path: exe.map(|exe| exe.to_string_lossy().into_owned()),
+            window_title: None,
+        };
+        let app_name = routing::resolve_app_name(&context);
+        println!("🔍 Resolved {} to app: {}", window.app_name, app_name);
+        Some(app_name)
+    }
+}
@@ -0,0 +1,134 @@
+// macOS backend: everything goes through System Events via `osascript`.
+
+use std::process::Command;
+
+use super::{WindowInfo, WindowSystem};
+use crate::routing::{self, AppContext};
+use crate::wrappers;
+
+pub struct MacWindowSystem;
+
+// Run an AppleScript and return its trimmed output, or `None` if it failed.
+fn osascript(script: &str) -> Option<String> {
+    let output = Command::new("osascript").arg("-e").arg(script).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// A string literal for AppleScript.
+fn quoted(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn split_list(list: &str) -> Vec<String> {
+    list.split(", ")
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// Name an Electron app from its bundle path. Routing rules get the first say,
+// since the bundle of some apps (DaVinci Resolve) doesn't end in their name.
+fn resolve_electron_bundle(bundle_path: &str) -> Option<String> {
+    if bundle_path.is_empty() {
+        return None;
+    }
+
+    let app_file = bundle_path.trim_end_matches(['/', ':']).rsplit(['/', ':']).next().unwrap_or(bundle_path);
+    let context = AppContext {
+        app_name: app_file.replace(".app", ""),
+        bundle_path: Some(bundle_path.to_string()),
+        window_title: None,
+    };
+    let app_name = routing::resolve_app_name(&context);
+    println!("🔍 Resolved Electron to app: {}", app_name);
+    Some(app_name)
+}
+
+impl WindowSystem for MacWindowSystem {
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn frontmost_window(&self) -> Option<WindowInfo> {
+        let app_name = osascript("tell application \"System Events\" to get name of application process 1 whose frontmost is true")?;
+        let pid = osascript("tell application \"System Events\" to get unix id of application process 1 whose frontmost is true")
+            .and_then(|pid| pid.parse().ok());
+        let title = osascript(
+            "tell application \"System Events\" to get name of front window of (first application process whose frontmost is true)",
+        )
+        .filter(|title| !title.is_empty());
+
+        Some(WindowInfo {
+            app_name,
+            title,
+            pid,
+            window_id: None,
+        })
+    }
+
+    // System Events only knows about apps, so this is one entry per visible
+    // app, in the order System Events reports them.
+    fn list_windows(&self) -> Vec<WindowInfo> {
+        osascript("tell application \"System Events\" to get name of every application process whose visible is true")
+            .map(|list| split_list(&list).iter().map(|name| WindowInfo::new(name)).collect())
+            .unwrap_or_default()
+    }
+
+    fn activate_app(&self, app_name: &str) -> bool {
+        // This is the safer approach - activate by name only, not by path
+        let cmd = format!("tell application {} to activate", quoted(app_name));
+        println!("🚀 Activating app with command: {}", cmd);
+        osascript(&cmd).is_some()
+    }
+
+    // System Events has no window ids, so the window is found by its process
+    // and title, raised within its app, and the app brought to the front.
+    fn activate_window(&self, window: &WindowInfo) -> bool {
+        let (Some(pid), Some(title)) = (window.pid, window.title.as_deref()) else {
+            return false;
+        };
+        let script = format!(
+            "tell application \"System Events\"
+                set appProcess to first application process whose unix id is {}
+                perform action \"AXRaise\" of (first window of appProcess whose name is {})
+                set frontmost of appProcess to true
+            end tell",
+            pid,
+            quoted(title)
+        );
+        println!("🚀 Activating window \"{}\" of {} ({})", title, window.app_name, pid);
+        osascript(&script).is_some()
+    }
+
+    fn is_running(&self, app_name: &str) -> bool {
+        // `is running` checks without launching the app, unlike `tell application`
+        osascript(&format!("application {} is running", quoted(app_name))).as_deref() == Some("true")
+    }
+
+    // Only opaque wrappers (Electron) need resolving here; every other process
+    // on macOS is already named after its app.
+    fn resolve_wrapper(&self, window: &WindowInfo) -> Option<String> {
+        if !wrappers::find(&window.app_name).is_some_and(|wrapper| wrapper.opaque) {
+            return None;
+        }
+        let pid = match window.pid {
+            Some(pid) => pid.to_string(),
+            None => osascript(&format!(
+                "tell application \"System Events\" to get unix id of first application process whose name is {}",
+                quoted(&window.app_name)
+            ))?,
+        };
+        println!("🔍 {} process PID: {}", window.app_name, pid);
+
+        let bundle_path = osascript(&format!(
+            "tell application \"System Events\" to get application file of application process id {}",
+            pid
+        ))?;
+        println!("🔍 Bundle path: {}", bundle_path);
+        resolve_electron_bundle(&bundle_path)
+    }
+}
@@ -0,0 +1,134 @@
+// In-memory window system for tests. Windows are kept front to back;
+// activating an app brings its first window to the front, activating a window
+// brings that one.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use super::{WindowInfo, WindowSystem};
+
+#[derive(Default)]
+pub struct MockWindowSystem {
+    windows: Mutex<Vec<WindowInfo>>,
+    // Wrapper app name → the app it runs.
+    wrappers: HashMap<String, String>,
+    // Apps whose activation fails.
+    refusing: HashSet<String>,
+    // Apps whose next activations report success but leave focus where it
+    // is, and how many.
+    ignoring: Mutex<HashMap<String, usize>>,
+    activations: Mutex<Vec<String>>,
+}
+
+impl MockWindowSystem {
+    // `apps` front to back, one window each.
+    pub fn with_apps(apps: &[&str]) -> Self {
+        Self {
+            windows: Mutex::new(apps.iter().map(|app| WindowInfo::new(app)).collect()),
+            ..Self::default()
+        }
+    }
+
+    // `windows` front to back.
+    pub fn with_windows(windows: Vec<WindowInfo>) -> Self {
+        Self {
+            windows: Mutex::new(windows),
+            ..Self::default()
+        }
+    }
+
+    pub fn close(&self, window: &WindowInfo) {
+        self.windows.lock().unwrap().retain(|w| !w.same_window(window));
+    }
+
+    pub fn wrapping(mut self, wrapper: &str, app: &str) -> Self {
+        self.wrappers.insert(wrapper.to_string(), app.to_string());
+        self
+    }
+
+    pub fn refusing(mut self, app: &str) -> Self {
+        self.refusing.insert(app.to_string());
+        self
+    }
+
+    pub fn ignoring(self, app: &str, times: usize) -> Self {
+        self.ignoring.lock().unwrap().insert(app.to_string(), times);
+        self
+    }
+
+    // Whether this activation of `app` is one to ignore.
+    fn ignores(&self, app: &str) -> bool {
+        match self.ignoring.lock().unwrap().get_mut(app) {
+            Some(times) if *times > 0 => {
+                *times -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Every app `activate_app` was called for, including refused ones.
+    pub fn activations(&self) -> Vec<String> {
+        self.activations.lock().unwrap().clone()
+    }
+
+    // Bring the first window matching `matches` to the front.
+    fn raise(&self, matches: impl Fn(&WindowInfo) -> bool) -> bool {
+        let mut windows = self.windows.lock().unwrap();
+        match windows.iter().position(matches) {
+            Some(index) => {
+                let window = windows.remove(index);
+                windows.insert(0, window);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl WindowSystem for MockWindowSystem {
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    fn frontmost_window(&self) -> Option<WindowInfo> {
+        self.windows.lock().unwrap().first().cloned()
+    }
+
+    fn list_windows(&self) -> Vec<WindowInfo> {
+        self.windows.lock().unwrap().clone()
+    }
+
+    fn activate_app(&self, app_name: &str) -> bool {
+        self.activations.lock().unwrap().push(app_name.to_string());
+        if self.refusing.contains(app_name) {
+            return false;
+        }
+        if self.ignores(app_name) {
+            return true;
+        }
+        self.raise(|w| w.app_name == app_name || self.wrappers.get(&w.app_name).map(String::as_str) == Some(app_name))
+    }
+
+    // Captured windows carry the resolved app name, so match on the rest.
+    fn activate_window(&self, window: &WindowInfo) -> bool {
+        if self.refusing.contains(&window.app_name) {
+            return false;
+        }
+        if self.ignores(&window.app_name) {
+            return true;
+        }
+        self.raise(|w| {
+            let resolved = self.resolve_wrapper(w).unwrap_or_else(|| w.app_name.clone());
+            WindowInfo { app_name: resolved, ..w.clone() }.same_window(window)
+        })
+    }
+
+    fn is_running(&self, app_name: &str) -> bool {
+        self.windows.lock().unwrap().iter().any(|w| w.app_name == app_name)
+    }
+
+    fn resolve_wrapper(&self, window: &WindowInfo) -> Option<String> {
+        self.wrappers.get(&window.app_name).cloned()
+    }
+}
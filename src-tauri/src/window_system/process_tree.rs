@@ -0,0 +1,183 @@
+// Finding what a wrapper process runs by walking `/proc`.
+//
+// Starting at the window's process and going up the parent chain, each
+// process is checked for packaging that names the app outright (an AppImage,
+// a Flatpak sandbox, a Snap), then for the wrapper's hint: the app argument
+// on the command line or the executable's install directory. The identity
+// found this way ("obsidian", "com.visualstudio.code", "Cursor") is turned
+// into the app's display name through `desktop`.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::wrappers::{self, Hint, Wrapper};
+
+// Parents to look at before giving up; wrappers sit close to their window.
+const MAX_DEPTH: usize = 8;
+
+// Path components that say where something is installed, not what it is.
+const GENERIC_DIRS: [&str; 12] = [
+    "usr", "lib", "lib64", "share", "opt", "local", "bin", "resources", "app", "out", "dist", "tmp",
+];
+
+struct Process {
+    pid: u32,
+    exe: Option<PathBuf>,
+    args: Vec<String>,
+}
+
+impl Process {
+    fn read(pid: u32) -> Option<Self> {
+        let cmdline = fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+        let args = cmdline
+            .split(|b| *b == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect();
+        Some(Self {
+            pid,
+            exe: fs::read_link(format!("/proc/{}/exe", pid)).ok(),
+            args,
+        })
+    }
+
+    fn name(&self) -> Option<String> {
+        fs::read_to_string(format!("/proc/{}/comm", self.pid))
+            .ok()
+            .map(|name| name.trim().to_string())
+    }
+
+    // Field 4 of `/proc/<pid>/stat`, after the parenthesised name (which may
+    // itself contain spaces and parentheses).
+    fn parent(&self) -> Option<u32> {
+        let stat = fs::read_to_string(format!("/proc/{}/stat", self.pid)).ok()?;
+        let (_, fields) = stat.rsplit_once(')')?;
+        fields.split_whitespace().nth(1)?.parse().ok()
+    }
+
+    fn env(&self, key: &str) -> Option<String> {
+        let environ = fs::read(format!("/proc/{}/environ", self.pid)).ok()?;
+        environ.split(|b| *b == 0).find_map(|entry| {
+            let entry = String::from_utf8_lossy(entry);
+            let (name, value) = entry.split_once('=')?;
+            (name == key && !value.is_empty()).then(|| value.to_string())
+        })
+    }
+
+    // `[Application] name=` in the sandbox's `.flatpak-info`.
+    fn flatpak_id(&self) -> Option<String> {
+        let info = fs::read_to_string(format!("/proc/{}/root/.flatpak-info", self.pid)).ok()?;
+        let mut in_application = false;
+        for line in info.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_application = line == "[Application]";
+            } else if let Some(name) = line.strip_prefix("name=").filter(|_| in_application) {
+                return Some(name.to_string());
+            }
+        }
+        None
+    }
+
+    fn snap_name(&self) -> Option<String> {
+        let exe = self.exe.as_ref()?;
+        let mut parts = exe.strip_prefix("/snap").ok()?.components();
+        Some(parts.next()?.as_os_str().to_string_lossy().into_owned())
+    }
+
+    // The identity the process itself gives away, if any.
+    fn identity(&self, hint: Hint) -> Option<String> {
+        if let Some(appimage) = self.env("APPIMAGE") {
+            return appimage_name(&appimage);
+        }
+        if let Some(id) = self.flatpak_id().or_else(|| self.snap_name()) {
+            return Some(id);
+        }
+        match hint {
+            Hint::AppArgument => self
+                .args
+                .iter()
+                .skip(1)
+                .filter(|arg| !arg.starts_with('-') && arg.contains('/'))
+                .find_map(|arg| app_from_path(Path::new(arg))),
+            Hint::InstallDir => app_from_path(self.exe.as_ref()?.parent()?),
+            Hint::AppImage => self.args.first().and_then(|arg0| appimage_name(arg0)),
+        }
+    }
+}
+
+// "Cursor-0.42.3-x86_64.AppImage" → "Cursor": the file name up to the dash or
+// underscore that starts a version or an architecture.
+fn appimage_name(path: &str) -> Option<String> {
+    let file = Path::new(path).file_name()?.to_string_lossy();
+    let stem = file.strip_suffix(".AppImage").or_else(|| file.strip_suffix(".appimage"))?;
+    let is_suffix = |rest: &str| {
+        let rest = rest.strip_prefix('v').unwrap_or(rest);
+        rest.starts_with(|c: char| c.is_ascii_digit()) || ["x86", "aarch64", "amd64", "arm"].iter().any(|arch| rest.starts_with(arch))
+    };
+    let end = stem
+        .match_indices(['-', '_'])
+        .map(|(i, _)| i)
+        .find(|i| is_suffix(&stem[i + 1..]))
+        .unwrap_or(stem.len());
+    let name = &stem[..end];
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+// The last directory of `path` that isn't a generic install directory:
+// `/usr/lib/obsidian/app.asar` → "obsidian", `/opt/Cursor/resources/app` →
+// "Cursor". Hidden directories, like an AppImage's `.mount_*`, are skipped.
+fn app_from_path(path: &Path) -> Option<String> {
+    let dir = if path.extension().is_some() { path.parent()? } else { path };
+    dir.components()
+        .filter_map(|part| match part {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .rfind(|name| {
+            let lower = name.to_lowercase();
+            !name.starts_with('.') && !GENERIC_DIRS.contains(&lower.as_str()) && !lower.starts_with("electron")
+        })
+}
+
+// The identity of the app `wrapper` runs in process `pid`, from the process
+// and the wrapper processes above it.
+pub fn identity(pid: u32, wrapper: &Wrapper) -> Option<String> {
+    let (mut pid, mut hint) = (pid, wrapper.hint);
+    for _ in 0..MAX_DEPTH {
+        let process = Process::read(pid)?;
+        if let Some(identity) = process.identity(hint) {
+            println!("🔍 Process {} runs {}", process.pid, identity);
+            return Some(identity);
+        }
+
+        // Only keep climbing through wrappers: above them are shells,
+        // terminals and the session, which say nothing about the app.
+        let parent = process.parent().filter(|parent| *parent > 1)?;
+        let parent_name = Process::read(parent)?.name()?;
+        hint = wrappers::find(&parent_name)?.hint;
+        pid = parent;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appimage_names_drop_versions_and_architectures() {
+        assert_eq!(appimage_name("/home/me/Apps/Cursor-0.42.3-x86_64.AppImage").as_deref(), Some("Cursor"));
+        assert_eq!(appimage_name("Obsidian-1.6.7.AppImage").as_deref(), Some("Obsidian"));
+        assert_eq!(appimage_name("LM_Studio-v0.3.5.appimage").as_deref(), Some("LM_Studio"));
+        assert_eq!(appimage_name("zed-aarch64.AppImage").as_deref(), Some("zed"));
+        assert_eq!(appimage_name("/usr/bin/cursor"), None);
+    }
+
+    #[test]
+    fn apps_are_named_after_their_install_directory() {
+        assert_eq!(app_from_path(Path::new("/usr/lib/obsidian/app.asar")).as_deref(), Some("obsidian"));
+        assert_eq!(app_from_path(Path::new("/opt/Cursor/resources/app")).as_deref(), Some("Cursor"));
+        assert_eq!(app_from_path(Path::new("/tmp/.mount_CursorXyz/usr/share/cursor/resources/app.asar")).as_deref(), Some("cursor"));
+        assert_eq!(app_from_path(Path::new("/usr/lib/electron28/resources")), None);
+    }
+}
@@ -0,0 +1,175 @@
+// Wayland through wlr-foreign-toplevel-management, which wlroots compositors
+// (Sway, Hyprland, river, labwc) and KDE implement. GNOME doesn't, so there
+// nothing is frontmost and activation fails, as on X11 without a server.
+//
+// The manager announces every toplevel with its `app_id` (e.g. "firefox",
+// "code") and title, and marks the focused one as activated. Wayland has no
+// pids or stacking order to offer, so windows come back in announcement order
+// with the activated one first, and a window is only told apart from the
+// app's others by its title.
+//
+// A connection is opened per call, like `x11`.
+
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::wl_registry::WlRegistry;
+use wayland_client::protocol::wl_seat::WlSeat;
+use wayland_client::{event_created_child, Connection, Dispatch, EventQueue, QueueHandle};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::zwlr_foreign_toplevel_handle_v1::{
+    self, ZwlrForeignToplevelHandleV1,
+};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::zwlr_foreign_toplevel_manager_v1::{
+    self, ZwlrForeignToplevelManagerV1,
+};
+
+use super::WindowInfo;
+
+struct Toplevel {
+    handle: ZwlrForeignToplevelHandleV1,
+    app_id: Option<String>,
+    title: Option<String>,
+    activated: bool,
+    closed: bool,
+}
+
+impl Toplevel {
+    fn info(&self) -> Option<WindowInfo> {
+        let app_id = self.app_id.as_ref().filter(|app_id| !app_id.is_empty())?;
+        Some(WindowInfo {
+            app_name: app_id.clone(),
+            title: self.title.clone().filter(|title| !title.is_empty()),
+            pid: None,
+            window_id: None,
+        })
+    }
+}
+
+#[derive(Default)]
+struct Toplevels {
+    toplevels: Vec<Toplevel>,
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for Toplevels {
+    fn event(_: &mut Self, _: &WlRegistry, _: <WlRegistry as wayland_client::Proxy>::Event, _: &GlobalListContents, _: &Connection, _: &QueueHandle<Self>) {}
+}
+
+impl Dispatch<WlSeat, ()> for Toplevels {
+    fn event(_: &mut Self, _: &WlSeat, _: <WlSeat as wayland_client::Proxy>::Event, _: &(), _: &Connection, _: &QueueHandle<Self>) {}
+}
+
+impl Dispatch<ZwlrForeignToplevelManagerV1, ()> for Toplevels {
+    fn event(
+        state: &mut Self,
+        _: &ZwlrForeignToplevelManagerV1,
+        event: zwlr_foreign_toplevel_manager_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } = event {
+            state.toplevels.push(Toplevel {
+                handle: toplevel,
+                app_id: None,
+                title: None,
+                activated: false,
+                closed: false,
+            });
+        }
+    }
+
+    event_created_child!(Toplevels, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrForeignToplevelHandleV1, ()> for Toplevels {
+    fn event(
+        state: &mut Self,
+        handle: &ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let Some(toplevel) = state.toplevels.iter_mut().find(|toplevel| toplevel.handle == *handle) else {
+            return;
+        };
+        match event {
+            zwlr_foreign_toplevel_handle_v1::Event::AppId { app_id } => toplevel.app_id = Some(app_id),
+            zwlr_foreign_toplevel_handle_v1::Event::Title { title } => toplevel.title = Some(title),
+            // An array of native-endian u32 states.
+            zwlr_foreign_toplevel_handle_v1::Event::State { state } => {
+                toplevel.activated = state
+                    .chunks_exact(4)
+                    .map(|bytes| u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                    .any(|value| value == zwlr_foreign_toplevel_handle_v1::State::Activated as u32);
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::Closed => toplevel.closed = true,
+            _ => {}
+        }
+    }
+}
+
+pub struct Wayland {
+    queue: EventQueue<Toplevels>,
+    state: Toplevels,
+    seat: Option<WlSeat>,
+}
+
+impl Wayland {
+    pub fn connect() -> Option<Self> {
+        let conn = match Connection::connect_to_env() {
+            Ok(conn) => conn,
+            Err(e) => {
+                println!("⚠️ Could not connect to the Wayland compositor: {}", e);
+                return None;
+            }
+        };
+        let (globals, mut queue) = registry_queue_init::<Toplevels>(&conn).ok()?;
+        let qh = queue.handle();
+        if globals.bind::<ZwlrForeignToplevelManagerV1, _, _>(&qh, 1..=3, ()).is_err() {
+            println!("⚠️ The compositor doesn't support wlr-foreign-toplevel-management");
+            return None;
+        }
+        let seat = globals.bind::<WlSeat, _, _>(&qh, 1..=1, ()).ok();
+
+        // The first roundtrip gets the toplevels, the second their details.
+        let mut state = Toplevels::default();
+        queue.roundtrip(&mut state).ok()?;
+        queue.roundtrip(&mut state).ok()?;
+        Some(Self { queue, state, seat })
+    }
+
+    fn open(&self) -> impl Iterator<Item = &Toplevel> {
+        self.state.toplevels.iter().filter(|toplevel| !toplevel.closed)
+    }
+
+    pub fn active_window(&self) -> Option<WindowInfo> {
+        self.open().find(|toplevel| toplevel.activated)?.info()
+    }
+
+    // Open toplevels, the activated one first.
+    pub fn windows(&self) -> Vec<WindowInfo> {
+        let (active, rest): (Vec<_>, Vec<_>) = self.open().partition(|toplevel| toplevel.activated);
+        active.into_iter().chain(rest).filter_map(Toplevel::info).collect()
+    }
+
+    // Ask the compositor to activate `window`, one of `windows()`.
+    pub fn activate(&mut self, window: &WindowInfo) -> bool {
+        let Some(seat) = self.seat.as_ref() else {
+            println!("⚠️ No Wayland seat to activate {} on", window.app_name);
+            return false;
+        };
+        let toplevel = self.open().find(|toplevel| toplevel.info().as_ref() == Some(window));
+        match toplevel {
+            Some(toplevel) => {
+                println!("🚀 Activating Wayland toplevel of {}", window.app_name);
+                toplevel.handle.activate(seat);
+                self.queue.roundtrip(&mut self.state).is_ok()
+            }
+            None => {
+                println!("⚠️ No Wayland toplevel found for {}", window.app_name);
+                false
+            }
+        }
+    }
+}
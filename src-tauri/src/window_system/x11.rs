@@ -0,0 +1,215 @@
+// X11 through EWMH, which every mainstream window manager implements.
+//
+// The active window comes from `_NET_ACTIVE_WINDOW` on the root window. A
+// window is identified by its id and described by the class half of
+// `WM_CLASS` (e.g. "firefox", "Code"), its `_NET_WM_NAME` title and its
+// `_NET_WM_PID`. Focus is restored by sending a `_NET_ACTIVE_WINDOW` client
+// message to the root window, the same request a taskbar makes when a button
+// is clicked.
+//
+// A connection is opened per call: these calls are rare, and a long-lived
+// connection would need to survive the X server going away.
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{AtomEnum, ClientMessageEvent, ConnectionExt, EventMask, Window};
+use x11rb::rust_connection::RustConnection;
+
+use super::WindowInfo;
+
+x11rb::atom_manager! {
+    Atoms: AtomsCookie {
+        _NET_ACTIVE_WINDOW,
+        _NET_CLIENT_LIST_STACKING,
+        _NET_WM_NAME,
+        _NET_WM_PID,
+        UTF8_STRING,
+    }
+}
+
+// `_NET_ACTIVE_WINDOW` source indication for pagers and taskbars, which
+// window managers honour more readily than requests from applications.
+const SOURCE_PAGER: u32 = 2;
+
+// `WM_CLASS` holds "instance\0class\0"; the class is the app's name. Some
+// clients set only the instance.
+fn wm_class(value: &[u8]) -> Option<String> {
+    let mut parts = value.split(|b| *b == 0).filter(|part| !part.is_empty());
+    let instance = parts.next()?;
+    let class = parts.next().unwrap_or(instance);
+    Some(String::from_utf8_lossy(class).into_owned())
+}
+
+// `_NET_WM_NAME` is UTF-8; a trailing NUL some clients include isn't part of
+// it. Invalid bytes are replaced rather than dropping the title.
+fn window_title(value: &[u8]) -> Option<String> {
+    let title = String::from_utf8_lossy(value);
+    let title = title.trim_end_matches('\0');
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+pub struct X11 {
+    conn: RustConnection,
+    root: Window,
+    atoms: Atoms,
+}
+
+impl X11 {
+    pub fn connect() -> Option<Self> {
+        let (conn, screen) = match RustConnection::connect(None) {
+            Ok(connection) => connection,
+            Err(e) => {
+                println!("⚠️ Could not connect to the X server: {}", e);
+                return None;
+            }
+        };
+        let root = conn.setup().roots.get(screen)?.root;
+        let atoms = Atoms::new(&conn).ok()?.reply().ok()?;
+        Some(Self { conn, root, atoms })
+    }
+
+    fn property(&self, window: Window, property: impl Into<u32>, type_: impl Into<u32>, length: u32) -> Option<Vec<u8>> {
+        let reply = self
+            .conn
+            .get_property(false, window, property, type_, 0, length)
+            .ok()?
+            .reply()
+            .ok()?;
+        (!reply.value.is_empty()).then_some(reply.value)
+    }
+
+    fn property32(&self, window: Window, property: impl Into<u32>, type_: impl Into<u32>, length: u32) -> Vec<u32> {
+        let reply = self
+            .conn
+            .get_property(false, window, property, type_, 0, length)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok());
+        reply
+            .and_then(|reply| reply.value32().map(|values| values.collect()))
+            .unwrap_or_default()
+    }
+
+    pub fn active_window(&self) -> Option<Window> {
+        self.property32(self.root, self.atoms._NET_ACTIVE_WINDOW, AtomEnum::WINDOW, 1)
+            .first()
+            .copied()
+            .filter(|window| *window != 0)
+    }
+
+    // Managed windows, most recently raised first.
+    pub fn client_windows(&self) -> Vec<Window> {
+        let mut windows = self.property32(self.root, self.atoms._NET_CLIENT_LIST_STACKING, AtomEnum::WINDOW, u32::MAX / 4);
+        windows.reverse();
+        windows
+    }
+
+    fn class(&self, window: Window) -> Option<String> {
+        wm_class(&self.property(window, AtomEnum::WM_CLASS, AtomEnum::STRING, 256)?)
+    }
+
+    fn title(&self, window: Window) -> Option<String> {
+        self.property(window, self.atoms._NET_WM_NAME, self.atoms.UTF8_STRING, 1024)
+            .or_else(|| self.property(window, AtomEnum::WM_NAME, AtomEnum::STRING, 1024))
+            .and_then(|title| window_title(&title))
+    }
+
+    fn pid(&self, window: Window) -> Option<u32> {
+        self.property32(window, self.atoms._NET_WM_PID, AtomEnum::CARDINAL, 1).first().copied()
+    }
+
+    pub fn window_info(&self, window: Window) -> Option<WindowInfo> {
+        Some(WindowInfo {
+            app_name: self.class(window)?,
+            title: self.title(window),
+            pid: self.pid(window),
+            window_id: Some(window.into()),
+        })
+    }
+
+    // Ask the window manager to activate `window`.
+    pub fn activate(&self, window: Window) -> bool {
+        let event = ClientMessageEvent::new(
+            32,
+            window,
+            self.atoms._NET_ACTIVE_WINDOW,
+            // Source, timestamp (0 = now), currently active window
+            [SOURCE_PAGER, 0, self.active_window().unwrap_or(0), 0, 0],
+        );
+        let sent = self
+            .conn
+            .send_event(
+                false,
+                self.root,
+                EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY,
+                event,
+            )
+            .is_ok();
+        sent && self.conn.flush().is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wm_class_is_the_second_string() {
+        assert_eq!(wm_class(b"code\0Code\0").as_deref(), Some("Code"));
+        assert_eq!(wm_class(b"Navigator\0firefox").as_deref(), Some("firefox"));
+        assert_eq!(wm_class(b"xterm\0").as_deref(), Some("xterm"));
+        assert_eq!(wm_class(b"\0\0"), None);
+    }
+
+    #[test]
+    fn window_titles_are_utf8() {
+        assert_eq!(window_title("main.rs — Cursor".as_bytes()).as_deref(), Some("main.rs — Cursor"));
+        assert_eq!(window_title(b"Terminal\0").as_deref(), Some("Terminal"));
+        assert_eq!(window_title(b"caf\xe9").as_deref(), Some("caf\u{fffd}"));
+        assert_eq!(window_title(b""), None);
+    }
+
+    // Needs an X server without a window manager, e.g.
+    // `Xvfb :99 & DISPLAY=:99 cargo test -- --ignored x11`. The test plays the
+    // window manager itself to see the activation request arrive.
+    #[test]
+    #[ignore]
+    fn reads_window_properties_and_requests_activation_on_xvfb() {
+        use x11rb::protocol::xproto::{ChangeWindowAttributesAux, CreateWindowAux, PropMode, WindowClass};
+        use x11rb::protocol::Event;
+        use x11rb::wrapper::ConnectionExt as _;
+
+        let x11 = X11::connect().expect("no X server, set DISPLAY");
+        let (conn, screen) = RustConnection::connect(None).unwrap();
+        let root = conn.setup().roots[screen].root;
+        conn.change_window_attributes(root, &ChangeWindowAttributesAux::new().event_mask(EventMask::SUBSTRUCTURE_REDIRECT))
+            .unwrap()
+            .check()
+            .expect("another window manager is running");
+
+        let window = conn.generate_id().unwrap();
+        conn.create_window(0, window, root, 0, 0, 100, 100, 0, WindowClass::INPUT_OUTPUT, 0, &CreateWindowAux::new())
+            .unwrap();
+        conn.change_property8(PropMode::REPLACE, window, AtomEnum::WM_CLASS, AtomEnum::STRING, b"code\0Code\0")
+            .unwrap();
+        conn.change_property8(PropMode::REPLACE, window, x11.atoms._NET_WM_NAME, x11.atoms.UTF8_STRING, "api — Cursor".as_bytes())
+            .unwrap();
+        conn.change_property32(PropMode::REPLACE, window, x11.atoms._NET_WM_PID, AtomEnum::CARDINAL, &[4242])
+            .unwrap();
+        conn.sync().unwrap();
+
+        let info = x11.window_info(window).unwrap();
+        assert_eq!(info.app_name, "Code");
+        assert_eq!(info.title.as_deref(), Some("api — Cursor"));
+        assert_eq!(info.pid, Some(4242));
+        assert_eq!(info.window_id, Some(window.into()));
+
+        assert!(x11.activate(window));
+        loop {
+            if let Event::ClientMessage(message) = conn.wait_for_event().unwrap() {
+                assert_eq!(message.window, window);
+                assert_eq!(message.type_, x11.atoms._NET_ACTIVE_WINDOW);
+                assert_eq!(message.data.as_data32()[0], SOURCE_PAGER);
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+// Known wrapper processes: runtimes and launchers whose process name or
+// window class says little about the app they run. On macOS that is Electron
+// when an app doesn't rename its process; on Linux VS Code, Cursor and other
+// Electron IDEs show up as `electron`, `code` or an AppImage's `AppRun`.
+//
+// The window system backend resolves a wrapper to the app it runs, using the
+// hint here for where the app's identity is found.
+
+use crate::routing::glob_matches;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hint {
+    // The app's directory or archive is an argument of the wrapper
+    // (`electron /usr/lib/obsidian/app.asar`).
+    AppArgument,
+    // The wrapper executable is the app's own, in the app's install directory
+    // (`/usr/share/code/code`, `/opt/Cursor/cursor`).
+    InstallDir,
+    // The wrapper runs the AppImage it was started from.
+    AppImage,
+}
+
+#[derive(Debug)]
+pub struct Wrapper {
+    // Process name or window class, a case-insensitive glob.
+    pub name: &'static str,
+    // Whether the name is useless on its own. An unresolved opaque wrapper is
+    // never remembered as the target app, since activating "Electron" would
+    // pick a random Electron app; other wrappers keep their name.
+    pub opaque: bool,
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub hint: Hint,
+}
+
+static KNOWN_WRAPPERS: &[Wrapper] = &[
+    Wrapper { name: "electron*", opaque: true, hint: Hint::AppArgument },
+    Wrapper { name: "AppRun*", opaque: true, hint: Hint::AppImage },
+    Wrapper { name: "code", opaque: false, hint: Hint::InstallDir },
+    Wrapper { name: "code-oss", opaque: false, hint: Hint::InstallDir },
+    Wrapper { name: "codium", opaque: false, hint: Hint::InstallDir },
+    Wrapper { name: "cursor", opaque: false, hint: Hint::InstallDir },
+    Wrapper { name: "windsurf", opaque: false, hint: Hint::InstallDir },
+];
+
+// The wrapper `name` (a process name or window class) belongs to, if any.
+pub fn find(name: &str) -> Option<&'static Wrapper> {
+    KNOWN_WRAPPERS.iter().find(|wrapper| glob_matches(wrapper.name, name))
+}
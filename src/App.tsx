@@ -1,6 +1,5 @@
-import { useState, useEffect, useCallback, useRef } from "react";
+import { useState, useEffect, useCallback, useMemo, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { Store } from "@tauri-apps/plugin-store";
 import { listen } from "@tauri-apps/api/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { WebviewWindow } from "@tauri-apps/api/webviewWindow";
@@ -8,7 +7,6 @@ import { Settings, X, Pencil, Crown, AlertCircle } from "lucide-react";
 import "./App.css";
 import { PhysicalPosition } from "@tauri-apps/api/window";
 import { confirm } from '@tauri-apps/plugin-dialog';
-import { emit } from "@tauri-apps/api/event";
 import { licenseManager } from "./services/license";
 
 interface Prompt {
@@ -19,6 +17,12 @@ interface Prompt {
   folderId?: string;
 }
 
+interface ChordPending {
+  typed: string;
+  hints: { mnemonic: string; promptId: string; title: string }[];
+  timeoutMs: number;
+}
+
 interface Folder {
   id: string;
   name: string;
@@ -27,57 +31,48 @@ interface Folder {
   prompts: Prompt[];
 }
 
-const DEFAULT_PROMPTS: Prompt[] = [
-  {
-    id: "1",
-    title: "Debug Root Cause",
-    content:
-      "Come up with 5-7 most likely root causes of this bug, and attempt the 1-2 most likely fixes with proper logging. Don't hold back, give it your all.",
-    color: "from-purple-500 to-pink-500",
-  },
-  {
-    id: "2",
-    title: "Explain Code",
-    content:
-      "Explain this code in detail, including its purpose, how it works, potential edge cases, and any improvements that could be made.",
-    color: "from-blue-500 to-cyan-500",
-  },
-  {
-    id: "3",
-    title: "Refactor",
-    content:
-      "Refactor this code to be more readable, maintainable, and performant. Follow best practices and explain your changes.",
-    color: "from-green-500 to-emerald-500",
-  },
-  {
-    id: "4",
-    title: "Write Tests",
-    content:
-      "Write comprehensive unit tests for this code, covering edge cases and error scenarios. Use appropriate testing patterns.",
-    color: "from-orange-500 to-red-500",
-  },
-  {
-    id: "5",
-    title: "Optimize Performance",
-    content:
-      "Analyze this code for performance bottlenecks and suggest specific optimizations with examples.",
-    color: "from-indigo-500 to-purple-500",
-  },
-  {
-    id: "6",
-    title: "Add Error Handling",
-    content:
-      "Add comprehensive error handling to this code with proper logging and user-friendly error messages.",
-    color: "from-teal-500 to-green-500",
-  },
-];
+interface PromptLibrary {
+  prompts: Prompt[];
+  folders: Folder[];
+}
+
+interface RankedPrompt {
+  prompt: Prompt;
+  score: number;
+  useCount: number;
+  lastUsed?: number;
+}
+
+interface RecentTarget {
+  id: number;
+  appName: string;
+  title?: string;
+  capturedAt: number;
+}
+
+interface PromptsChanged {
+  added: Prompt[];
+  updated: Prompt[];
+  removed: string[];
+  order: string[];
+  folders: Folder[];
+}
 
 function App() {
-  const [prompts, setPrompts] = useState<Prompt[]>(DEFAULT_PROMPTS);
+  const [prompts, setPrompts] = useState<Prompt[]>([]);
   const [folders, setFolders] = useState<Folder[]>([]);
+  const [ranking, setRanking] = useState<string[]>([]);
   const [expandedIndex, setExpandedIndex] = useState<number | null>(null);
   const [injectedId, setInjectedId] = useState<string | null>(null);
   const [errorMessage, setErrorMessage] = useState<string>("");
+  const [chord, setChord] = useState<ChordPending | null>(null);
+  const [routedFolderId, setRoutedFolderId] = useState<string | null>(null);
+  // The folder whose name is being edited in place, and the name typed so far
+  const [renamingFolderId, setRenamingFolderId] = useState<string | null>(null);
+  const [folderNameDraft, setFolderNameDraft] = useState("");
+  const [recentTargets, setRecentTargets] = useState<RecentTarget[]>([]);
+  // Set when the user picks an older target than the app the bar opened over
+  const [chosenTargetId, setChosenTargetId] = useState<number | null>(null);
   const [hasProLicense, setHasProLicense] = useState(false);
   const [promptLimitInfo, setPromptLimitInfo] = useState({
     isAtLimit: false,
@@ -112,27 +107,38 @@ function App() {
     }
   }, []);
 
+  /* --------------------------------------------------
+   * Frecency ranking – most used prompts get the first slots
+   * -------------------------------------------------- */
+  const loadRanking = useCallback(async () => {
+    try {
+      const ranked = await invoke<RankedPrompt[]>("get_ranked_prompts");
+      setRanking(ranked.map((r) => r.prompt.id));
+    } catch (error) {
+      console.error('❌ Error loading prompt ranking:', error);
+    }
+  }, []);
+
+  const rankedPrompts = useMemo(() => {
+    const rankOf = (p: Prompt) => {
+      const rank = ranking.indexOf(p.id);
+      return rank === -1 ? Number.MAX_SAFE_INTEGER : rank;
+    };
+    return [...prompts].sort((a, b) => rankOf(a) - rankOf(b));
+  }, [prompts, ranking]);
+
   /* --------------------------------------------------
    * Load & persist prompts
    * -------------------------------------------------- */
   const loadPrompts = useCallback(async () => {
     console.log('🔄 loadPrompts called');
     try {
-      const store = await Store.load("prompts.json");
-      console.log('📁 Store loaded');
-      let saved = await store.get<Prompt[]>("prompts");
-      let savedFolders = await store.get<Folder[]>("folders") || [];
-      console.log('📋 Raw saved prompts:', saved, 'folders:', savedFolders);
-      if (!saved || saved.length === 0) {
-        console.log('📋 No saved prompts, using defaults');
-        saved = DEFAULT_PROMPTS;
-        await store.set("prompts", saved);
-        await store.save();
-        console.log('💾 Default prompts saved to store');
-      }
-      console.log('✅ Setting prompts to:', saved);
+      const library = await invoke<PromptLibrary>("list_prompts");
+      const saved = library.prompts;
+      console.log('📋 Loaded prompts:', saved, 'folders:', library.folders);
       setPrompts(saved);
-      setFolders(savedFolders);
+      setFolders(library.folders);
+      await loadRanking();
       
       // Update license info and prompt limits
       await loadLicenseInfo();
@@ -142,7 +148,7 @@ function App() {
     } catch (error) {
       console.error('❌ Error in loadPrompts:', error);
     }
-  }, [loadLicenseInfo, updatePromptLimits]);
+  }, [loadLicenseInfo, updatePromptLimits, loadRanking]);
 
   useEffect(() => {
     console.log('🚀 Initial loadPrompts call');
@@ -157,65 +163,55 @@ function App() {
    * Global shortcut listener from the backend
    * -------------------------------------------------- */
   useEffect(() => {
-    const unlistenPromise = listen<number>(
+    // The payload is the id of the prompt whose own shortcut was pressed.
+    const unlistenPromise = listen<string>(
       "inject-prompt",
-      ({ payload: index }) => {
-        if (index >= 0 && index < prompts.length) {
-          injectTextViaShortcut(prompts[index], index + 1);
+      ({ payload: promptId }) => {
+        const index = rankedPrompts.findIndex((p) => p.id === promptId);
+        if (index >= 0) {
+          injectTextViaShortcut(rankedPrompts[index], index + 1);
         }
       }
     );
     return () => {
       unlistenPromise.then((unlisten) => unlisten());
     };
-  }, [prompts]);
+  }, [rankedPrompts]);
+
+  /* --------------------------------------------------
+   * Leader chord hints
+   * -------------------------------------------------- */
+  useEffect(() => {
+    const unlistenPending = listen<ChordPending>("chord-pending", ({ payload }) => setChord(payload));
+    const unlistenEnded = listen<string>("chord-ended", () => setChord(null));
+    return () => {
+      unlistenPending.then((unlisten) => unlisten());
+      unlistenEnded.then((unlisten) => unlisten());
+    };
+  }, []);
 
   useEffect(() => {
-    console.log('👂 Setting up prompts-updated listeners');
-    
-    // Primary event listener
-    const unlistenPromise1 = listen("prompts-updated", (event) => {
-      console.log('🔔 prompts-updated event received!', event);
-      loadPrompts();
-    });
-    
-    // Secondary listener with different approach
-    const unlistenPromise2 = listen("prompts-updated", () => {
-      console.log('🔔 Secondary prompts-updated listener triggered');
-      setTimeout(() => loadPrompts(), 50);
+    // The backend emits this after every prompt mutation, whichever window
+    // made it, so the bar never needs to re-read the store itself.
+    const unlistenPromise = listen<PromptsChanged>("prompts-changed", ({ payload }) => {
+      console.log('🔔 prompts-changed event received', payload);
+      setPrompts((current) => {
+        const byId = new Map(current.map((p) => [p.id, p]));
+        payload.removed.forEach((id) => byId.delete(id));
+        [...payload.added, ...payload.updated].forEach((p) => byId.set(p.id, p));
+        const next = payload.order
+          .map((id) => byId.get(id))
+          .filter((p): p is Prompt => p !== undefined);
+        updatePromptLimits(next.length);
+        return next;
+      });
+      setFolders(payload.folders);
     });
-    
-    // Polling mechanism as backup - check for store changes every 2 seconds
-    const pollInterval = setInterval(async () => {
-      try {
-        const store = await Store.load("prompts.json");
-        const saved = await store.get<Prompt[]>("prompts") || [];
-        const trigger = await store.get("_trigger");
-        
-        // If trigger timestamp exists and is recent (last 5 seconds), reload
-        if (trigger && typeof trigger === 'number' && Date.now() - trigger < 5000) {
-          console.log('🔄 Store trigger detected, reloading prompts');
-          await store.set("_trigger", null); // Clear trigger
-          await store.save();
-          loadPrompts();
-        }
-        
-        // Also check if prompt count changed
-        if (saved.length !== prompts.length) {
-          console.log(`📊 Prompt count changed: ${prompts.length} → ${saved.length}`);
-          loadPrompts();
-        }
-      } catch (error) {
-        console.log('📊 Polling check failed:', error);
-      }
-    }, 2000);
-    
+
     return () => {
-      unlistenPromise1.then((unlisten) => unlisten());
-      unlistenPromise2.then((unlisten) => unlisten());
-      clearInterval(pollInterval);
+      unlistenPromise.then((unlisten) => unlisten());
     };
-  }, [loadPrompts, prompts.length]);
+  }, [updatePromptLimits]);
 
   /* --------------------------------------------------
    * Keyboard listener – press 1-9 to inject prompts
@@ -228,28 +224,41 @@ function App() {
       const key = e.key;
       if (key >= "1" && key <= "9") {
         const index = parseInt(key, 10) - 1;
-        if (index < prompts.length) {
+        if (index < rankedPrompts.length) {
           e.preventDefault();
-          injectTextViaShortcut(prompts[index], index + 1);
+          injectTextViaShortcut(rankedPrompts[index], index + 1);
         }
       }
     };
 
     window.addEventListener("keydown", handleKeyDown);
     return () => window.removeEventListener("keydown", handleKeyDown);
-  }, [prompts]);
+  }, [rankedPrompts]);
 
   useEffect(() => {
     const unlistenPromise = getCurrentWindow().onFocusChanged(({ payload: focused }) => {
       if (!focused) {
         setExpandedIndex(null);
+        return;
       }
+      // Rankings are per target app, and that may have changed since last time
+      loadRanking();
+      // A routing rule can put one folder's prompts first for the current app
+      invoke<{ folderId?: string }>("get_current_route")
+        .then((route) => setRoutedFolderId(route.folderId ?? null))
+        .catch((error) => console.error('❌ Error loading current route:', error));
+      invoke<RecentTarget[]>("list_recent_targets")
+        .then((targets) => {
+          setRecentTargets(targets);
+          setChosenTargetId(null);
+        })
+        .catch((error) => console.error('❌ Error loading recent targets:', error));
     });
 
     return () => {
       unlistenPromise.then((unlisten) => unlisten());
     };
-  }, []);
+  }, [loadRanking]);
 
   /* --------------------------------------------------
    * Hover handlers
@@ -275,14 +284,21 @@ function App() {
       // Show a brief message to explain what's happening
       setErrorMessage("🖱️ Click anywhere to trigger injection...");
       
-      await invoke<string>("inject_text_at_cursor", { text: prompt.content });
+      if (chosenTargetId !== null) {
+        await invoke("activate_target", { id: chosenTargetId });
+        setChosenTargetId(null);
+      }
+      await invoke<string>("inject_text_at_cursor", { text: prompt.content, promptId: prompt.id });
       setInjectedId(prompt.id);
+      loadRanking();
       setErrorMessage("✅ Text injected successfully!");
       setTimeout(() => {
         setInjectedId(null);
         setErrorMessage("");
       }, 2000);
     } catch (e) {
+      // Closing the variable form isn't an error worth reporting.
+      if (e === "Injection cancelled") return;
       console.error(e);
       const errorMessage = typeof e === 'string' ? e : 'Unknown error occurred';
       setErrorMessage(`❌ ${errorMessage}`);
@@ -323,7 +339,7 @@ function App() {
   /* --------------------------------------------------
    * Open Edit Window
    * -------------------------------------------------- */
-  const openEditWindow = async (index: number, pill?: HTMLElement) => {
+  const openEditWindow = async (index: number, pill?: HTMLElement, promptId?: string) => {
     console.log(`openEditWindow called for index ${index}`);
     try {
       const label = index === -1 ? 'edit-add' : `edit-${index}`;
@@ -359,7 +375,7 @@ function App() {
         console.warn(`No element provided for positioning edit window index ${index}`);
       }
 
-      const urlParam = index === -1 ? 'add' : `edit=${index}`;
+      const urlParam = index === -1 ? 'add' : `edit=${index}&id=${encodeURIComponent(promptId ?? '')}`;
       const windowTitle = index === -1 ? 'Add New Prompt' : `Edit Prompt ${index + 1}`;
 
       const newWin = new WebviewWindow(label, {
@@ -383,10 +399,7 @@ function App() {
     }
   };
 
-  const deletePrompt = async (index: number) => {
-    const promptToDelete = prompts[index];
-    if (!promptToDelete) return;
-
+  const deletePrompt = async (promptToDelete: Prompt) => {
     const confirmed = await confirm(
       `Are you sure you want to delete the prompt "${promptToDelete.title}"?`,
       { title: "Confirm Deletion", kind: "warning" }
@@ -395,21 +408,7 @@ function App() {
     if (!confirmed) return;
 
     try {
-      const store = await Store.load("prompts.json");
-      let saved = await store.get<Prompt[]>("prompts") || [];
-      
-      // Find the index by id to be safe
-      const storeIndex = saved.findIndex(p => p.id === promptToDelete.id);
-      if (storeIndex !== -1) {
-        saved.splice(storeIndex, 1);
-        await store.set("prompts", saved);
-        await store.save();
-        await emit("prompts-updated");
-        // Assuming 'emit' is a global function or imported elsewhere,
-        // otherwise, you might need to use a different event emitter.
-        // For now, we'll just reload and update the state.
-        loadPrompts();
-      }
+      await invoke("delete_prompt", { id: promptToDelete.id });
     } catch (error) {
       console.error('Error deleting prompt:', error);
       // Optionally show error message
@@ -423,25 +422,50 @@ function App() {
     if (!hasProLicense) return;
     
     try {
-      const store = await Store.load("prompts.json");
-      let savedFolders = await store.get<Folder[]>("folders") || [];
-      
-      const folderIndex = savedFolders.findIndex(f => f.id === folderId);
-      if (folderIndex !== -1) {
-        savedFolders[folderIndex].isExpanded = !savedFolders[folderIndex].isExpanded;
-        await store.set("folders", savedFolders);
-        await store.save();
-        setFolders(savedFolders);
-      }
+      await invoke<Folder>("toggle_folder", { folderId });
     } catch (error) {
       console.error('Error toggling folder:', error);
     }
   };
 
+  const startRenamingFolder = (folder: Folder) => {
+    if (!hasProLicense) return;
+    setRenamingFolderId(folder.id);
+    setFolderNameDraft(folder.name);
+  };
+
+  // Called when the name input loses focus, which Enter and Escape also cause
+  const renameFolder = async (folder: Folder, name: string) => {
+    setRenamingFolderId(null);
+    if (!name.trim() || name.trim() === folder.name) return;
+
+    try {
+      await invoke<Folder>("rename_folder", { folderId: folder.id, name: name.trim() });
+    } catch (error) {
+      console.error('Error renaming folder:', error);
+    }
+  };
+
+  // Deleting a folder keeps its prompts; they move back to the main bar
+  const deleteFolder = async (folderToDelete: Folder) => {
+    const confirmed = await confirm(
+      `Delete the folder "${folderToDelete.name}"? Its prompts will be kept.`,
+      { title: "Confirm Deletion", kind: "warning" }
+    );
+
+    if (!confirmed) return;
+
+    try {
+      await invoke("delete_folder", { folderId: folderToDelete.id });
+    } catch (error) {
+      console.error('Error deleting folder:', error);
+    }
+  };
+
 
   // Get prompts that are not in any folder (for display in main bar)
   const getUnfolderedPrompts = () => {
-    return prompts.filter(p => !p.folderId);
+    return rankedPrompts.filter(p => !p.folderId);
   };
 
   // Get all prompts in expanded folders (for display in main bar)
@@ -452,11 +476,13 @@ function App() {
       .slice(0, Math.max(0, 9 - getUnfolderedPrompts().length));
   };
 
-  // Get final list of prompts to display in the main bar
+  // Get final list of prompts to display in the main bar. The folder picked
+  // by a routing rule comes first, expanded or not.
   const getDisplayPrompts = () => {
+    const routedPrompts = rankedPrompts.filter(p => routedFolderId && p.folderId === routedFolderId);
     const unfolderedPrompts = getUnfolderedPrompts();
-    const expandedFolderPrompts = getExpandedFolderPrompts();
-    return [...unfolderedPrompts, ...expandedFolderPrompts].slice(0, 9);
+    const expandedFolderPrompts = getExpandedFolderPrompts().filter(p => p.folderId !== routedFolderId);
+    return [...routedPrompts, ...unfolderedPrompts, ...expandedFolderPrompts].slice(0, 9);
   };
 
   /* --------------------------------------------------
@@ -489,12 +515,8 @@ function App() {
     setDragOverPromptId(null);
     
     try {
-      const store = await Store.load("prompts.json");
-      let savedPrompts = await store.get<Prompt[]>("prompts") || [];
-      let savedFolders = await store.get<Folder[]>("folders") || [];
-      
-      const draggedPrompt = savedPrompts.find(p => p.id === draggedPromptId);
-      const targetPrompt = savedPrompts.find(p => p.id === targetPromptId);
+      const draggedPrompt = prompts.find(p => p.id === draggedPromptId);
+      const targetPrompt = prompts.find(p => p.id === targetPromptId);
       
       if (!draggedPrompt || !targetPrompt) {
         console.error('Could not find dragged or target prompt');
@@ -502,53 +524,23 @@ function App() {
         return;
       }
       
-      // Check if target prompt is already in a folder
-      const existingFolder = savedFolders.find(f => f.prompts.some(p => p.id === targetPromptId));
-      
-      if (existingFolder) {
-        // Add dragged prompt to existing folder
-        if (!existingFolder.prompts.some(p => p.id === draggedPromptId)) {
-          draggedPrompt.folderId = existingFolder.id;
-          existingFolder.prompts.push(draggedPrompt);
-          
-          // Remove from any other folders
-          savedFolders.forEach(folder => {
-            if (folder.id !== existingFolder.id) {
-              folder.prompts = folder.prompts.filter(p => p.id !== draggedPromptId);
-            }
+      if (targetPrompt.folderId) {
+        // Add dragged prompt to the target's existing folder
+        if (draggedPrompt.folderId !== targetPrompt.folderId) {
+          await invoke<PromptLibrary>("move_prompt", {
+            id: draggedPromptId,
+            folderId: targetPrompt.folderId,
           });
         }
       } else {
         // Create new folder with both prompts
         const folderName = `${targetPrompt.title} & ${draggedPrompt.title}`.substring(0, 20);
-        const newFolder: Folder = {
-          id: `folder_${Date.now()}`,
+        await invoke<Folder>("create_folder", {
           name: folderName,
-          color: "from-gray-500 to-gray-600",
-          isExpanded: true,
-          prompts: [targetPrompt, draggedPrompt]
-        };
-        
-        // Update prompt folder references
-        targetPrompt.folderId = newFolder.id;
-        draggedPrompt.folderId = newFolder.id;
-        
-        // Remove prompts from any existing folders
-        savedFolders.forEach(folder => {
-          folder.prompts = folder.prompts.filter(p => p.id !== targetPromptId && p.id !== draggedPromptId);
+          promptIds: [targetPromptId, draggedPromptId],
         });
-        
-        savedFolders.push(newFolder);
       }
-      
-      await store.set("prompts", savedPrompts);
-      await store.set("folders", savedFolders);
-      await store.save();
-      
-      setPrompts(savedPrompts);
-      setFolders(savedFolders);
-      
-      await emit("prompts-updated");
+
     } catch (error) {
       console.error('Error creating folder:', error);
     } finally {
@@ -584,9 +576,45 @@ function App() {
                   </div>
                 </div>
                 <div className="folder-info">
-                  <div className="folder-name">{folder.name}</div>
+                  {renamingFolderId === folder.id ? (
+                    <input
+                      className="folder-rename-input"
+                      value={folderNameDraft}
+                      autoFocus
+                      onChange={(e) => setFolderNameDraft(e.target.value)}
+                      onClick={(e) => e.stopPropagation()}
+                      onBlur={(e) => renameFolder(folder, e.currentTarget.value)}
+                      onKeyDown={(e) => {
+                        e.stopPropagation();
+                        if (e.key === 'Escape') e.currentTarget.value = folder.name;
+                        if (e.key === 'Enter' || e.key === 'Escape') e.currentTarget.blur();
+                      }}
+                      data-tauri-drag-region="false"
+                    />
+                  ) : (
+                    <div
+                      className="folder-name"
+                      title="Double-click to rename"
+                      onDoubleClick={(e) => {
+                        e.stopPropagation();
+                        startRenamingFolder(folder);
+                      }}
+                    >
+                      {folder.name}
+                    </div>
+                  )}
                   <div className="folder-count">{folder.prompts.length}</div>
                 </div>
+                <button
+                  className="delete-btn delete-btn-corner"
+                  onClick={(e) => {
+                    e.stopPropagation();
+                    deleteFolder(folder);
+                  }}
+                  data-tauri-drag-region="false"
+                >
+                  <X size={12} />
+                </button>
                 <div className={`folder-gradient bg-gradient-to-r ${folder.color}`} />
               </div>
             </div>
@@ -616,7 +644,7 @@ function App() {
                   e.preventDefault();
                   e.stopPropagation();
                   const pill = pillRefs.current[i];
-                  openEditWindow(i, pill ?? undefined);
+                  openEditWindow(i, pill ?? undefined, p.id);
                 }
               }}
               data-tauri-drag-region="false"
@@ -629,7 +657,7 @@ function App() {
                     e.stopPropagation();
                     console.log(`Edit button clicked for prompt ${i + 1}`);
                     const pill = pillRefs.current[i];
-                    openEditWindow(i, pill ?? undefined);
+                    openEditWindow(i, pill ?? undefined, p.id);
                   }}
                   data-tauri-drag-region="false"
                 >
@@ -641,7 +669,7 @@ function App() {
                 className="delete-btn delete-btn-corner"
                 onClick={(e) => {
                   e.stopPropagation();
-                  deletePrompt(i);
+                  deletePrompt(p);
                 }}
                 data-tauri-drag-region="false"
               >
@@ -732,6 +760,37 @@ function App() {
         </div>
       </div>
 
+      {/* Where the next prompt goes, when there's more than one candidate */}
+      {recentTargets.length > 1 && (
+        <div className="target-picker" data-tauri-drag-region="false">
+          <span>Inject into</span>
+          <select
+            value={chosenTargetId ?? recentTargets[0].id}
+            onChange={(e) => {
+              const id = Number(e.target.value);
+              setChosenTargetId(id === recentTargets[0].id ? null : id);
+            }}
+          >
+            {recentTargets.map((target) => (
+              <option key={target.id} value={target.id}>
+                {target.title ? `${target.appName} — ${target.title}` : target.appName}
+              </option>
+            ))}
+          </select>
+        </div>
+      )}
+
+      {/* Pending chord hints */}
+      {chord && (
+        <div className="chord-hints">
+          {chord.hints.map((hint) => (
+            <span key={hint.promptId} className="chord-hint">
+              <kbd>{hint.mnemonic}</kbd> {hint.title}
+            </span>
+          ))}
+        </div>
+      )}
+
       {/* Error toast */}
       {errorMessage && <div className="error-toast">{errorMessage}</div>}
 
@@ -1,10 +1,10 @@
 import { useState, useEffect } from "react";
-import { Store } from "@tauri-apps/plugin-store";
-import { emit } from "@tauri-apps/api/event";
+import { invoke } from "@tauri-apps/api/core";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { Wand2, Sparkles, Loader2, Crown, Lock } from "lucide-react";
 import { createOpenRouterClient } from "./services/openrouter";
 import { licenseManager } from "./services/license";
+import ShortcutRecorder from "./ShortcutRecorder";
 import "./App.css";
 
 interface Prompt {
@@ -13,6 +13,9 @@ interface Prompt {
   content: string;
   color: string;
   folderId?: string;
+  keyMacro?: string;
+  shortcut?: string;
+  mnemonic?: string;
 }
 
 interface Folder {
@@ -23,54 +26,15 @@ interface Folder {
   prompts: Prompt[];
 }
 
-const DEFAULT_PROMPTS: Prompt[] = [
-  {
-    id: "1",
-    title: "Debug Root Cause",
-    content:
-      "Come up with 5-7 most likely root causes of this bug, and attempt the 1-2 most likely fixes with proper logging. Don't hold back, give it your all.",
-    color: "from-purple-500 to-pink-500",
-  },
-  {
-    id: "2",
-    title: "Explain Code",
-    content:
-      "Explain this code in detail, including its purpose, how it works, potential edge cases, and any improvements that could be made.",
-    color: "from-blue-500 to-cyan-500",
-  },
-  {
-    id: "3",
-    title: "Refactor",
-    content:
-      "Refactor this code to be more readable, maintainable, and performant. Follow best practices and explain your changes.",
-    color: "from-green-500 to-emerald-500",
-  },
-  {
-    id: "4",
-    title: "Write Tests",
-    content:
-      "Write comprehensive unit tests for this code, covering edge cases and error scenarios. Use appropriate testing patterns.",
-    color: "from-orange-500 to-red-500",
-  },
-  {
-    id: "5",
-    title: "Optimize Performance",
-    content:
-      "Analyze this code for performance bottlenecks and suggest specific optimizations with examples.",
-    color: "from-indigo-500 to-purple-500",
-  },
-  {
-    id: "6",
-    title: "Add Error Handling",
-    content:
-      "Add comprehensive error handling to this code with proper logging and user-friendly error messages.",
-    color: "from-teal-500 to-green-500",
-  },
-];
+interface PromptLibrary {
+  prompts: Prompt[];
+  folders: Folder[];
+}
 
 function PromptEditor() {
   const urlParams = new URLSearchParams(window.location.search);
   const editParam = urlParams.get("edit");
+  const editId = urlParams.get("id");
   const isAddMode = urlParams.has("add");
   const index = isAddMode ? -1 : parseInt(editParam || "-1", 10);
 
@@ -79,6 +43,9 @@ function PromptEditor() {
   const [prompt, setPrompt] = useState<Prompt | null>(null);
   const [title, setTitle] = useState("");
   const [content, setContent] = useState("");
+  const [keyMacro, setKeyMacro] = useState("");
+  const [shortcut, setShortcut] = useState("");
+  const [mnemonic, setMnemonic] = useState("");
   const [loaded, setLoaded] = useState(false);
   const [enhancementRequest, setEnhancementRequest] = useState("");
   const [isEnhancing, setIsEnhancing] = useState(false);
@@ -95,9 +62,8 @@ function PromptEditor() {
     const load = async () => {
       try {
         console.log('Starting load');
-        const store = await Store.load('prompts.json');
-        console.log('Store loaded');
-        let saved = await store.get<Prompt[]>('prompts') || [];
+        const library = await invoke<PromptLibrary>('list_prompts');
+        const saved = library.prompts;
         console.log('Got saved prompts:', saved);
         
         // Check license status
@@ -105,15 +71,19 @@ function PromptEditor() {
         setHasProLicense(proLicense);
         
         // Load folders
-        let savedFolders = await store.get<Folder[]>("folders") || [];
-        setFolders(savedFolders);
+        setFolders(library.folders);
         
-        if (saved.length === 0) saved = DEFAULT_PROMPTS;
-        if (index >= 0 && index < saved.length) {
-          const p = saved[index];
+        // The bar orders pills by usage, so look the prompt up by id and only
+        // fall back to the position for windows opened without one.
+        const byId = editId ? saved.find((p) => p.id === editId) : undefined;
+        if (index >= 0 && (byId || index < saved.length)) {
+          const p = byId ?? saved[index];
           setPrompt(p);
           setTitle(p.title);
           setContent(p.content);
+          setKeyMacro(p.keyMacro || "");
+          setShortcut(p.shortcut || "");
+          setMnemonic(p.mnemonic || "");
           setSelectedFolderId(p.folderId || null);
         } else if (index === -1) {
           const defaultColor = 'from-blue-500 to-cyan-500';
@@ -158,101 +128,34 @@ function PromptEditor() {
     }
     
     try {
-      console.log('📁 Loading store...');
-      const store = await Store.load("prompts.json");
-      
-      console.log('📋 Getting current prompts...');
-      let saved = await store.get<Prompt[]>("prompts") || [];
-      console.log('📋 Current saved prompts:', saved);
-      
-      if (saved.length === 0) {
-        saved = DEFAULT_PROMPTS;
-        console.log('📋 Using default prompts as base');
-      }
+      const draft = {
+        title: title.trim(),
+        content: content.trim(),
+        color: prompt.color,
+        folderId: selectedFolderId,
+        keyMacro: keyMacro.trim() || null,
+        shortcut: shortcut.trim() || null,
+        mnemonic: mnemonic.trim() || null,
+      };
       
-      if (index >= 0 && index < saved.length) {
-        console.log('✏️ Editing existing prompt at index:', index);
-        saved[index] = { ...prompt, title: title.trim(), content: content.trim(), folderId: selectedFolderId || undefined };
-      } else if (index === -1) {
+      if (index >= 0) {
+        console.log('✏️ Editing existing prompt:', prompt.id);
+        await invoke<Prompt>("update_prompt", { id: prompt.id, prompt: draft });
+      } else {
         console.log('➕ Adding new prompt');
         
         // Check if user can add another prompt
-        const canAddResult = await licenseManager.canAddPrompt(saved.length);
+        const library = await invoke<PromptLibrary>("list_prompts");
+        const canAddResult = await licenseManager.canAddPrompt(library.prompts.length);
         if (!canAddResult.canAdd) {
           alert(`🔒 Prompt Limit Reached\n\n${canAddResult.reason}`);
           return;
         }
         
-        const newId = Date.now().toString();
-        const newPrompt = { 
-          id: newId, 
-          title: title.trim(), 
-          content: content.trim(), 
-          color: prompt.color,
-          folderId: selectedFolderId || undefined
-        };
+        const newPrompt = await invoke<Prompt>("create_prompt", { prompt: draft });
         console.log('🆕 New prompt object:', newPrompt);
-        saved.push(newPrompt);
-      }
-      
-      // Update folder contents if needed
-      if (hasProLicense && selectedFolderId) {
-        let savedFolders = await store.get<Folder[]>("folders") || [];
-        const targetFolder = savedFolders.find(f => f.id === selectedFolderId);
-        if (targetFolder) {
-          const promptId = index >= 0 ? prompt?.id : Date.now().toString();
-          // Remove prompt from all folders first
-          savedFolders.forEach(folder => {
-            folder.prompts = folder.prompts.filter(p => p.id !== promptId);
-          });
-          // Add to target folder
-          const promptToAdd = saved.find(p => p.id === promptId);
-          if (promptToAdd) {
-            targetFolder.prompts.push(promptToAdd);
-          }
-          await store.set("folders", savedFolders);
-        }
-      }
-      
-      console.log('💾 Final prompts array to save:', saved);
-      console.log('📏 Array length:', saved.length);
-      
-      // Save to store with explicit operations
-      await store.set("prompts", saved);
-      console.log('📝 Store.set completed');
-      
-      await store.save();
-      console.log('💾 Store.save completed');
-      
-      // Try multiple event emission approaches
-      try {
-        console.log('📡 Attempting event emission...');
-        
-        // Approach 1: Simple emit
-        await emit("prompts-updated");
-        console.log('✅ Basic prompts-updated event emitted');
-        
-        // Approach 2: Emit with payload
-        await emit("prompts-updated", { 
-          timestamp: Date.now(),
-          action: index === -1 ? 'add' : 'edit',
-          promptCount: saved.length 
-        });
-        console.log('✅ Enhanced prompts-updated event emitted');
-        
-        // Approach 3: Force reload by touching the store again
-        await store.set("_trigger", Date.now());
-        await store.save();
-        console.log('✅ Store trigger updated');
-        
-      } catch (eventError) {
-        console.error('❌ Event emission failed:', eventError);
       }
       
-      // Wait longer to ensure event processing
-      console.log('⏳ Waiting for event processing...');
-      await new Promise(resolve => setTimeout(resolve, 300));
-      
       console.log('🪟 Closing window...');
       const win = getCurrentWindow();
       await win.close();
@@ -331,21 +234,12 @@ function PromptEditor() {
     if (!hasProLicense || !newFolderName.trim()) return;
     
     try {
-      const store = await Store.load('prompts.json');
-      let savedFolders = await store.get<Folder[]>("folders") || [];
-      
-      const newFolder: Folder = {
-        id: `folder_${Date.now()}`,
+      const newFolder = await invoke<Folder>("create_folder", {
         name: newFolderName.trim(),
-        color: "from-gray-500 to-gray-600",
-        isExpanded: true,
-        prompts: []
-      };
+        promptIds: [],
+      });
       
-      savedFolders.push(newFolder);
-      await store.set("folders", savedFolders);
-      await store.save();
-      setFolders(savedFolders);
+      setFolders([...folders, newFolder]);
       setNewFolderName('');
       setShowCreateFolder(false);
       setSelectedFolderId(newFolder.id);
@@ -492,6 +386,41 @@ function PromptEditor() {
         />
       </label>
 
+      <label>
+        Shortcut (optional):
+        <input
+          type="text"
+          value={shortcut}
+          onChange={(e) => setShortcut(e.target.value)}
+          placeholder="e.g. cmd+alt+1"
+          className="editor-input"
+        />
+        <ShortcutRecorder kind="prompt" promptId={prompt?.id || undefined} onRecorded={setShortcut} />
+      </label>
+
+      <label>
+        Chord letters (optional):
+        <input
+          type="text"
+          value={mnemonic}
+          onChange={(e) => setMnemonic(e.target.value)}
+          placeholder="e.g. rf, typed after the chord leader"
+          maxLength={2}
+          className="editor-input"
+        />
+      </label>
+
+      <label>
+        Key macro (optional):
+        <input
+          type="text"
+          value={keyMacro}
+          onChange={(e) => setKeyMacro(e.target.value)}
+          placeholder="e.g. {selectall}{backspace}{text}{enter}"
+          className="editor-input"
+        />
+      </label>
+
       {/* AI Enhancement Section */}
       {content && (
         <div className={`ai-enhancement-section ${!hasProLicense ? 'locked-section' : ''}`}>
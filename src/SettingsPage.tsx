@@ -1,23 +1,117 @@
 import { useState, useEffect } from "react";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { Store } from "@tauri-apps/plugin-store";
-import { Crown, Key, Copy, Target } from "lucide-react";
+import { Crown, Key, Copy, Target, ClipboardPaste } from "lucide-react";
 import { licenseManager, LicenseInfo } from "./services/license";
+import ShortcutRecorder from "./ShortcutRecorder";
 import "./App.css";
 
 interface Settings {
   toggleShortcut: string;
   targetMode: string;
   targetAppName: string;
+  injectionStrategy: string;
+  pasteThreshold: number;
+  clipboardRestoreDelayMs: number;
+  newlinePolicy: string;
+  newlineOverrides: Record<string, string>;
+  leaderShortcut: string;
+  chordTimeoutMs: number;
+  routingRules: RoutingRule[];
+  ignoreList: IgnoreList;
+}
+
+interface IgnoreList {
+  allow: string[];
+  deny: string[];
+}
+
+interface Verdict {
+  accepted: boolean;
+  reason: string;
+}
+
+interface RoutingRule {
+  name: string;
+  enabled: boolean;
+  field: "appName" | "windowTitle";
+  syntax: "glob" | "regex";
+  pattern: string;
+  targetApp?: string;
+  injectionStrategy?: string;
+  newlinePolicy?: string;
+  folderId?: string;
+}
+
+interface ShortcutStatus {
+  shortcut: string;
+  kind: "toggle" | "leader" | "prompt";
+  promptId?: string;
+  state: "registered" | "conflict" | "failed";
+  error?: string;
+  conflictsWith?: string;
+}
+
+const SHORTCUT_STATE_LABELS: Record<ShortcutStatus["state"], string> = {
+  registered: "Active",
+  conflict: "Conflict",
+  failed: "Failed",
+};
+
+interface SaveSettingsResult {
+  settings: Settings;
+  toggleShortcut: ShortcutStatus;
+  leaderShortcut?: ShortcutStatus;
+}
+
+interface MigrationReport {
+  file: string;
+  fromVersion: number;
+  toVersion: number;
+  status: string;
+  error?: string;
+  backupPath?: string;
 }
 
 const DEFAULT_SETTINGS: Settings = {
   toggleShortcut: "alt+shift+space",
   targetMode: "auto",
-  targetAppName: ""
+  targetAppName: "",
+  injectionStrategy: "auto",
+  pasteThreshold: 200,
+  clipboardRestoreDelayMs: 500,
+  newlinePolicy: "shift-enter",
+  newlineOverrides: {},
+  leaderShortcut: "",
+  chordTimeoutMs: 1500,
+  routingRules: [],
+  ignoreList: { allow: [], deny: [] }
+};
+
+const EMPTY_RULE: RoutingRule = {
+  name: "",
+  enabled: true,
+  field: "appName",
+  syntax: "glob",
+  pattern: ""
 };
 
+const NEWLINE_POLICIES: [string, string][] = [
+  ["shift-enter", "Shift+Enter (line break in chat boxes)"],
+  ["enter", "Enter (as typed)"],
+  ["paste", "Paste the whole prompt"],
+  ["spaces", "Join lines with spaces"],
+];
+
+// Same bounds as `injection::validate` in the backend.
+const MAX_PASTE_THRESHOLD = 100000;
+const MIN_RESTORE_DELAY_MS = 100;
+const MAX_RESTORE_DELAY_MS = 10000;
+
+const clamp = (value: number, min: number, max: number) => Math.min(max, Math.max(min, value));
+
 // Convert internal shortcut format to macOS display format
 const formatShortcutForDisplay = (shortcut: string): string => {
   return shortcut
@@ -76,6 +170,7 @@ const keyEventToShortcut = (event: KeyboardEvent): string => {
 
 function SettingsPage() {
   const [settings, setSettings] = useState<Settings>(DEFAULT_SETTINGS);
+  const [newOverrideApp, setNewOverrideApp] = useState("");
   const [isEditing, setIsEditing] = useState(false);
   const [tempShortcut, setTempShortcut] = useState("");
   const [license, setLicense] = useState<LicenseInfo>({ key: '', tier: 'free', isValid: false });
@@ -83,6 +178,17 @@ function SettingsPage() {
   const [isActivatingLicense, setIsActivatingLicense] = useState(false);
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
+  const [migrationErrors, setMigrationErrors] = useState<MigrationReport[]>([]);
+  const [shortcutError, setShortcutError] = useState<string | null>(null);
+  const [leaderDraft, setLeaderDraft] = useState<string | null>(null);
+  const [shortcutStatuses, setShortcutStatuses] = useState<ShortcutStatus[]>([]);
+  const [promptTitles, setPromptTitles] = useState<Record<string, string>>({});
+  const [folderNames, setFolderNames] = useState<[string, string][]>([]);
+  const [rulesDraft, setRulesDraft] = useState<RoutingRule[] | null>(null);
+  const [rulesError, setRulesError] = useState<string | null>(null);
+  const [ignoreDraft, setIgnoreDraft] = useState<{ allow: string; deny: string } | null>(null);
+  const [testAppName, setTestAppName] = useState("");
+  const [testVerdict, setTestVerdict] = useState<string | null>(null);
 
   console.log("SettingsPage: Component mounted");
   console.log("SettingsPage: Settings state:", settings);
@@ -92,6 +198,8 @@ function SettingsPage() {
     const initializeSettings = async () => {
       try {
         await loadSettings();
+        await loadMigrationStatus();
+        await loadShortcutStatus();
         await loadLicense();
       } catch (err) {
         console.error("SettingsPage: Initialization error:", err);
@@ -104,6 +212,25 @@ function SettingsPage() {
     initializeSettings();
   }, []);
 
+  // Keep in sync with settings saved from other windows
+  useEffect(() => {
+    const unlistenPromise = listen<{ changed: string[]; settings: Settings }>(
+      "settings-changed",
+      ({ payload }) => setSettings(payload.settings)
+    );
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, []);
+
+  // Prompt edits re-sync prompt shortcuts, so refresh their badges.
+  useEffect(() => {
+    const unlistenPromise = listen("prompts-changed", () => loadShortcutStatus());
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, []);
+
   // Add keydown event listener for automatic shortcut detection
   useEffect(() => {
     const handleKeyDown = (event: KeyboardEvent) => {
@@ -161,6 +288,27 @@ function SettingsPage() {
     }
   };
 
+  const loadMigrationStatus = async () => {
+    try {
+      const reports = await invoke<MigrationReport[]>("get_migration_status");
+      setMigrationErrors(reports.filter((r) => r.status === "failed"));
+    } catch (error) {
+      console.error("SettingsPage: Failed to load migration status:", error);
+    }
+  };
+
+  const loadShortcutStatus = async () => {
+    try {
+      const statuses = await invoke<ShortcutStatus[]>("get_shortcut_status");
+      const library = await invoke<{ prompts: { id: string; title: string }[]; folders: { id: string; name: string }[] }>("list_prompts");
+      setShortcutStatuses(statuses);
+      setPromptTitles(Object.fromEntries(library.prompts.map((p) => [p.id, p.title])));
+      setFolderNames(library.folders.map((f) => [f.id, f.name]));
+    } catch (error) {
+      console.error("SettingsPage: Failed to load shortcut status:", error);
+    }
+  };
+
   const loadLicense = async () => {
     try {
       const licenseInfo = await licenseManager.getLicenseInfo();
@@ -170,21 +318,89 @@ function SettingsPage() {
     }
   };
 
+  const addNewlineOverride = () => {
+    const appName = newOverrideApp.trim();
+    if (!appName) return;
+    saveSettings({
+      ...settings,
+      newlineOverrides: { ...settings.newlineOverrides, [appName]: settings.newlinePolicy },
+    });
+    setNewOverrideApp("");
+  };
+
+  const removeNewlineOverride = (appName: string) => {
+    const newlineOverrides = { ...settings.newlineOverrides };
+    delete newlineOverrides[appName];
+    saveSettings({ ...settings, newlineOverrides });
+  };
+
+  const rules = rulesDraft ?? settings.routingRules;
+
+  const updateRule = (index: number, changes: Partial<RoutingRule>) => {
+    setRulesDraft(rules.map((rule, i) => (i === index ? { ...rule, ...changes } : rule)));
+  };
+
+  const moveRule = (index: number, offset: number) => {
+    const target = index + offset;
+    if (target < 0 || target >= rules.length) return;
+    const reordered = [...rules];
+    [reordered[index], reordered[target]] = [reordered[target], reordered[index]];
+    setRulesDraft(reordered);
+  };
+
+  // Rules are saved as a whole so a half-typed regex isn't rejected on every
+  // keystroke, and never through the store fallback, which skips validation.
+  const saveRoutingRules = async () => {
+    try {
+      const result = await invoke<SaveSettingsResult>("save_settings", { settings: { ...settings, routingRules: rules } });
+      setSettings(result.settings);
+      setRulesDraft(null);
+      setRulesError(null);
+    } catch (error) {
+      setRulesError(String(error));
+    }
+  };
+
+  // One pattern per line, edited as text and saved as a whole.
+  const ignoreText = ignoreDraft ?? {
+    allow: settings.ignoreList.allow.join("\n"),
+    deny: settings.ignoreList.deny.join("\n"),
+  };
+
+  const saveIgnoreList = async () => {
+    const toPatterns = (text: string) => text.split("\n").map((line) => line.trim()).filter(Boolean);
+    await saveSettings({
+      ...settings,
+      ignoreList: { allow: toPatterns(ignoreText.allow), deny: toPatterns(ignoreText.deny) },
+    });
+    setIgnoreDraft(null);
+    setTestVerdict(null);
+  };
+
+  const testTargetApp = async () => {
+    try {
+      const verdict = await invoke<Verdict>("test_target_app", { name: testAppName });
+      setTestVerdict(`${verdict.accepted ? "✅ Accepted" : "🚫 Rejected"}: ${testAppName.trim()} ${verdict.reason}`);
+    } catch (error) {
+      setTestVerdict(`⚠️ ${error}`);
+    }
+  };
+
   const saveSettings = async (newSettings: Settings) => {
     try {
-      await invoke("save_settings", { settings: newSettings });
-      setSettings(newSettings);
+      const result = await invoke<SaveSettingsResult>("save_settings", { settings: newSettings });
+      setSettings(result.settings);
+      const rejected = [result.toggleShortcut, result.leaderShortcut].find(
+        (status) => status && status.state !== "registered"
+      );
+      setShortcutError(rejected ? rejected.error ?? "Shortcut is not active" : null);
+      await loadShortcutStatus();
     } catch (error) {
       console.error("Failed to save settings:", error);
-      // Fallback to store method for backward compatibility
-      try {
-        const store = await Store.load("settings.json");
-        await store.set("settings", newSettings);
-        await store.save();
-        setSettings(newSettings);
-      } catch (fallbackError) {
-        console.error("Fallback settings save failed:", fallbackError);
-      }
+      // No writing the store directly here: the backend refuses to save over
+      // a settings file it couldn't read, and that has to stay refused.
+      alert(`Settings were not saved: ${error}`);
+      await loadMigrationStatus();
     }
   };
 
@@ -277,6 +493,13 @@ function SettingsPage() {
           <>
             <h3 className="settings-title">Settings</h3>
 
+        {migrationErrors.map((report) => (
+          <p key={report.file} className="settings-note" style={{ color: 'red' }}>
+            ⚠️ Could not upgrade {report.file}: {report.error}
+            {report.backupPath && ` (your original file is saved at ${report.backupPath})`}
+          </p>
+        ))}
+
         {/* License Section */}
         <div className="settings-section">
           <h4 className="section-title">
@@ -411,7 +634,302 @@ function SettingsPage() {
               💡 Auto mode: Automatically targets the second most recently used app
             </p>
             <p className="settings-hint">
-              🎯 Manual mode: Always targets the specified app (useful for apps that are hard to detect like Qoder)
+              🎯 Manual mode: Always targets the specified app (useful for apps that are hard to detect like Qoder). The app must already be running
+            </p>
+          </div>
+
+          <h4 className="section-title">Routing rules</h4>
+          {rules.map((rule, index) => (
+            <div className="routing-rule" key={index}>
+              <div className="settings-item">
+                <input
+                  type="checkbox"
+                  checked={rule.enabled}
+                  onChange={(e) => updateRule(index, { enabled: e.target.checked })}
+                  data-tauri-drag-region="false"
+                />
+                <input
+                  type="text"
+                  value={rule.name}
+                  onChange={(e) => updateRule(index, { name: e.target.value })}
+                  placeholder="Rule name"
+                  className="app-name-input"
+                  data-tauri-drag-region="false"
+                />
+                <button onClick={() => moveRule(index, -1)} className="cancel-btn" data-tauri-drag-region="false">↑</button>
+                <button onClick={() => moveRule(index, 1)} className="cancel-btn" data-tauri-drag-region="false">↓</button>
+                <button onClick={() => setRulesDraft(rules.filter((_, i) => i !== index))} className="cancel-btn" data-tauri-drag-region="false">
+                  Remove
+                </button>
+              </div>
+              <div className="settings-item">
+                <select
+                  value={rule.field}
+                  onChange={(e) => updateRule(index, { field: e.target.value as RoutingRule["field"] })}
+                  className="app-name-input"
+                  data-tauri-drag-region="false"
+                >
+                  <option value="appName">App name</option>
+                  <option value="windowTitle">Window title</option>
+                </select>
+                <select
+                  value={rule.syntax}
+                  onChange={(e) => updateRule(index, { syntax: e.target.value as RoutingRule["syntax"] })}
+                  className="app-name-input"
+                  data-tauri-drag-region="false"
+                >
+                  <option value="glob">matches glob</option>
+                  <option value="regex">matches regex</option>
+                </select>
+                <input
+                  type="text"
+                  value={rule.pattern}
+                  onChange={(e) => updateRule(index, { pattern: e.target.value })}
+                  placeholder={rule.syntax === "glob" ? "e.g. *Cursor*" : "e.g. \\.rs\\b"}
+                  className="app-name-input"
+                  data-tauri-drag-region="false"
+                />
+              </div>
+              <div className="settings-item">
+                <input
+                  type="text"
+                  value={rule.targetApp ?? ""}
+                  onChange={(e) => updateRule(index, { targetApp: e.target.value || undefined })}
+                  placeholder="Inject into app (optional)"
+                  className="app-name-input"
+                  data-tauri-drag-region="false"
+                />
+                <select
+                  value={rule.injectionStrategy ?? ""}
+                  onChange={(e) => updateRule(index, { injectionStrategy: e.target.value || undefined })}
+                  className="app-name-input"
+                  data-tauri-drag-region="false"
+                >
+                  <option value="">Default method</option>
+                  <option value="type">Type</option>
+                  <option value="paste">Paste</option>
+                  <option value="auto">Auto</option>
+                </select>
+                <select
+                  value={rule.newlinePolicy ?? ""}
+                  onChange={(e) => updateRule(index, { newlinePolicy: e.target.value || undefined })}
+                  className="app-name-input"
+                  data-tauri-drag-region="false"
+                >
+                  <option value="">Default line breaks</option>
+                  {NEWLINE_POLICIES.map(([value, label]) => (
+                    <option key={value} value={value}>{label}</option>
+                  ))}
+                </select>
+                <select
+                  value={rule.folderId ?? ""}
+                  onChange={(e) => updateRule(index, { folderId: e.target.value || undefined })}
+                  className="app-name-input"
+                  data-tauri-drag-region="false"
+                >
+                  <option value="">No folder</option>
+                  {folderNames.map(([id, name]) => (
+                    <option key={id} value={id}>{name}</option>
+                  ))}
+                </select>
+              </div>
+            </div>
+          ))}
+
+          <div className="settings-item">
+            <button onClick={() => setRulesDraft([...rules, { ...EMPTY_RULE }])} className="save-btn" data-tauri-drag-region="false">
+              Add rule
+            </button>
+            {rulesDraft && (
+              <>
+                <button onClick={saveRoutingRules} className="save-btn" data-tauri-drag-region="false">
+                  Save rules
+                </button>
+                <button onClick={() => { setRulesDraft(null); setRulesError(null); }} className="cancel-btn" data-tauri-drag-region="false">
+                  Discard
+                </button>
+              </>
+            )}
+          </div>
+          {rulesError && (
+            <p className="settings-note" style={{ color: 'red' }}>
+              ⚠️ {rulesError}
+            </p>
+          )}
+
+          <div className="settings-hints">
+            <p className="settings-hint">
+              🧭 Rules are checked top to bottom against the app the bar was opened over; each setting comes from the first matching rule that sets it
+            </p>
+          </div>
+
+          <h4 className="section-title">Ignored apps</h4>
+          <div className="settings-item">
+            <span className="settings-label">Never target:</span>
+            <textarea
+              value={ignoreText.deny}
+              onChange={(e) => setIgnoreDraft({ ...ignoreText, deny: e.target.value })}
+              placeholder="One pattern per line, e.g. *Agent"
+              className="editor-textarea ignore-list-patterns"
+              data-tauri-drag-region="false"
+            />
+          </div>
+          <div className="settings-item">
+            <span className="settings-label">Always allow:</span>
+            <textarea
+              value={ignoreText.allow}
+              onChange={(e) => setIgnoreDraft({ ...ignoreText, allow: e.target.value })}
+              placeholder="One pattern per line, e.g. Finder"
+              className="editor-textarea ignore-list-patterns"
+              data-tauri-drag-region="false"
+            />
+          </div>
+          {ignoreDraft && (
+            <div className="settings-item">
+              <button onClick={saveIgnoreList} className="save-btn" data-tauri-drag-region="false">
+                Save ignored apps
+              </button>
+              <button onClick={() => setIgnoreDraft(null)} className="cancel-btn" data-tauri-drag-region="false">
+                Discard
+              </button>
+            </div>
+          )}
+          <div className="settings-item">
+            <input
+              type="text"
+              value={testAppName}
+              onChange={(e) => { setTestAppName(e.target.value); setTestVerdict(null); }}
+              onKeyDown={(e) => e.key === "Enter" && testTargetApp()}
+              placeholder="Test an app name"
+              className="app-name-input"
+              data-tauri-drag-region="false"
+            />
+            <button onClick={testTargetApp} className="save-btn" data-tauri-drag-region="false">
+              Test
+            </button>
+          </div>
+          {testVerdict && <p className="settings-note">{testVerdict}</p>}
+
+          <div className="settings-hints">
+            <p className="settings-hint">
+              🚫 Ignored apps are never focused or typed into. Patterns are globs (* and ?) and ignore case; an allow pattern wins over a deny pattern. Testing uses the saved lists
+            </p>
+          </div>
+        </div>
+
+        {/* Injection Method Section */}
+        <div className="settings-section">
+          <h4 className="section-title">
+            <ClipboardPaste size={16} />
+            Injection Method
+          </h4>
+
+          <div className="settings-item">
+            <span className="settings-label">Insert prompts by:</span>
+            <div className="target-mode-options">
+              {[
+                ["auto", "Auto (paste long prompts, type short ones)"],
+                ["type", "Typing (one key at a time)"],
+                ["paste", "Pasting (via the clipboard)"],
+              ].map(([value, label]) => (
+                <label className="radio-option" key={value}>
+                  <input
+                    type="radio"
+                    name="injectionStrategy"
+                    value={value}
+                    checked={settings.injectionStrategy === value}
+                    onChange={(e) => saveSettings({ ...settings, injectionStrategy: e.target.value })}
+                    data-tauri-drag-region="false"
+                  />
+                  <span>{label}</span>
+                </label>
+              ))}
+            </div>
+          </div>
+
+          {settings.injectionStrategy === "auto" && (
+            <div className="settings-item">
+              <span className="settings-label">Paste prompts longer than (characters):</span>
+              <input
+                type="number"
+                min={0}
+                max={MAX_PASTE_THRESHOLD}
+                value={settings.pasteThreshold}
+                onChange={(e) => saveSettings({ ...settings, pasteThreshold: clamp(parseInt(e.target.value, 10) || 0, 0, MAX_PASTE_THRESHOLD) })}
+                className="app-name-input"
+                data-tauri-drag-region="false"
+              />
+            </div>
+          )}
+
+          {settings.injectionStrategy !== "type" && (
+            <div className="settings-item">
+              <span className="settings-label">Restore clipboard after (ms):</span>
+              <input
+                type="number"
+                min={MIN_RESTORE_DELAY_MS}
+                max={MAX_RESTORE_DELAY_MS}
+                step={100}
+                value={settings.clipboardRestoreDelayMs}
+                onChange={(e) => saveSettings({ ...settings, clipboardRestoreDelayMs: clamp(parseInt(e.target.value, 10) || 0, MIN_RESTORE_DELAY_MS, MAX_RESTORE_DELAY_MS) })}
+                className="app-name-input"
+                data-tauri-drag-region="false"
+              />
+            </div>
+          )}
+
+          <div className="settings-item">
+            <span className="settings-label">Line breaks:</span>
+            <select
+              value={settings.newlinePolicy}
+              onChange={(e) => saveSettings({ ...settings, newlinePolicy: e.target.value })}
+              className="app-name-input"
+              data-tauri-drag-region="false"
+            >
+              {NEWLINE_POLICIES.map(([value, label]) => (
+                <option key={value} value={value}>{label}</option>
+              ))}
+            </select>
+          </div>
+
+          {Object.entries(settings.newlineOverrides).map(([appName, policy]) => (
+            <div className="settings-item" key={appName}>
+              <span className="settings-label">In {appName}:</span>
+              <select
+                value={policy}
+                onChange={(e) => saveSettings({ ...settings, newlineOverrides: { ...settings.newlineOverrides, [appName]: e.target.value } })}
+                className="app-name-input"
+                data-tauri-drag-region="false"
+              >
+                {NEWLINE_POLICIES.map(([value, label]) => (
+                  <option key={value} value={value}>{label}</option>
+                ))}
+              </select>
+              <button onClick={() => removeNewlineOverride(appName)} className="cancel-btn" data-tauri-drag-region="false">
+                Remove
+              </button>
+            </div>
+          ))}
+
+          <div className="settings-item">
+            <span className="settings-label">Line breaks for a specific app:</span>
+            <input
+              type="text"
+              value={newOverrideApp}
+              onChange={(e) => setNewOverrideApp(e.target.value)}
+              onKeyDown={(e) => e.key === "Enter" && addNewlineOverride()}
+              placeholder="e.g. Cursor, Terminal"
+              className="app-name-input"
+              data-tauri-drag-region="false"
+            />
+            <button onClick={addNewlineOverride} className="save-btn" data-tauri-drag-region="false">
+              Add
+            </button>
+          </div>
+
+          <div className="settings-hints">
+            <p className="settings-hint">
+              📋 Pasting is instant and avoids editor autocomplete; your clipboard is put back afterwards
             </p>
           </div>
         </div>
@@ -457,16 +975,79 @@ function SettingsPage() {
                 >
                   Edit
                 </button>
+                <ShortcutRecorder
+                  kind="toggle"
+                  onRecorded={(shortcut) => saveSettings({ ...settings, toggleShortcut: shortcut })}
+                />
               </div>
             )}
           </div>
+          {shortcutError && (
+            <p className="settings-note" style={{ color: 'red' }}>
+              ⚠️ {shortcutError}
+            </p>
+          )}
+
+          <div className="settings-item">
+            <span className="settings-label">Chord leader:</span>
+            <input
+              type="text"
+              value={leaderDraft ?? settings.leaderShortcut}
+              onChange={(e) => setLeaderDraft(e.target.value)}
+              onBlur={() => {
+                if (leaderDraft !== null && leaderDraft !== settings.leaderShortcut) {
+                  saveSettings({ ...settings, leaderShortcut: formatShortcutForStorage(leaderDraft.trim()) });
+                }
+                setLeaderDraft(null);
+              }}
+              placeholder="e.g. alt+shift+p (empty to disable)"
+              className="app-name-input"
+              data-tauri-drag-region="false"
+            />
+            <ShortcutRecorder
+              kind="leader"
+              onRecorded={(shortcut) => saveSettings({ ...settings, leaderShortcut: shortcut })}
+            />
+          </div>
+
+          <div className="settings-item">
+            <span className="settings-label">Chord timeout (ms):</span>
+            <input
+              type="number"
+              min={300}
+              step={100}
+              value={settings.chordTimeoutMs}
+              onChange={(e) => saveSettings({ ...settings, chordTimeoutMs: Number(e.target.value) })}
+              className="app-name-input"
+              data-tauri-drag-region="false"
+            />
+          </div>
+
+          {shortcutStatuses
+            .filter((status) => status.promptId)
+            .map((status) => (
+              <div className="settings-item" key={status.promptId}>
+                <span className="settings-label">{promptTitles[status.promptId!] ?? status.promptId}:</span>
+                <span className="settings-value">{formatShortcutForDisplay(status.shortcut)}</span>
+                <span className={`shortcut-badge ${status.state}`} title={status.error}>
+                  {SHORTCUT_STATE_LABELS[status.state]}
+                </span>
+              </div>
+            ))}
+          {shortcutStatuses
+            .filter((status) => status.state !== "registered")
+            .map((status) => (
+              <p key={`${status.promptId ?? "toggle"}-error`} className="settings-note" style={{ color: 'red' }}>
+                {formatShortcutForDisplay(status.shortcut)}: {status.error}
+              </p>
+            ))}
         </div>
 
         <div className="settings-section shortcuts-hints">
-          <p className="settings-hint">Use Cmd + Option + 1-9 to inject prompts</p>
+          <p className="settings-hint">Give a prompt its own shortcut in the prompt editor to inject it from anywhere</p>
+          <p className="settings-hint">Or press the chord leader, then a prompt's chord letters</p>
           <p className="settings-hint">Use 1-9 keys when prompt picker is focused</p>
           <p className="settings-hint">Press your current shortcut to edit it quickly</p>
-          <p className="settings-note">Note: Shortcut changes require app restart</p>
         </div>
 
         <button
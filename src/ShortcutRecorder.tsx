@@ -0,0 +1,99 @@
+import { useState, useEffect, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import "./App.css";
+
+interface RecordedShortcut {
+  shortcut: string;
+  conflictsWith?: string;
+}
+
+interface ShortcutRecorderProps {
+  kind: "toggle" | "leader" | "prompt";
+  promptId?: string;
+  onRecorded: (shortcut: string) => void;
+}
+
+// "Record" button that captures the next key combination through the backend,
+// which also reports whether another binding already uses it.
+function ShortcutRecorder({ kind, promptId, onRecorded }: ShortcutRecorderProps) {
+  const [isRecording, setIsRecording] = useState(false);
+  const [hint, setHint] = useState<string | null>(null);
+  const [conflict, setConflict] = useState<string | null>(null);
+  const recordingRef = useRef(false);
+
+  useEffect(() => {
+    if (!isRecording) return;
+
+    const handleKeyDown = (event: KeyboardEvent) => {
+      event.preventDefault();
+      event.stopPropagation();
+
+      const hasModifier = event.ctrlKey || event.altKey || event.shiftKey || event.metaKey;
+      if (event.code === "Escape" && !hasModifier) {
+        invoke("cancel_shortcut_recording");
+        return;
+      }
+
+      invoke("submit_recorded_keys", {
+        keys: {
+          code: event.code,
+          ctrl: event.ctrlKey,
+          alt: event.altKey,
+          shift: event.shiftKey,
+          meta: event.metaKey
+        }
+      })
+        .then(() => setHint(null))
+        .catch((error) => setHint(String(error)));
+    };
+
+    // Capture phase so the page's own shortcut handling never sees the keys.
+    window.addEventListener("keydown", handleKeyDown, true);
+    return () => {
+      window.removeEventListener("keydown", handleKeyDown, true);
+    };
+  }, [isRecording]);
+
+  // Don't leave a recording running if the field goes away mid-recording.
+  useEffect(() => {
+    return () => {
+      if (recordingRef.current) invoke("cancel_shortcut_recording");
+    };
+  }, []);
+
+  const startRecording = async () => {
+    recordingRef.current = true;
+    setIsRecording(true);
+    setHint(null);
+    setConflict(null);
+    try {
+      const recorded = await invoke<RecordedShortcut | null>("record_shortcut", { kind, promptId: promptId ?? null });
+      if (recorded) {
+        setConflict(recorded.conflictsWith ?? null);
+        onRecorded(recorded.shortcut);
+      }
+    } catch (error) {
+      setHint(String(error));
+    } finally {
+      recordingRef.current = false;
+      setIsRecording(false);
+    }
+  };
+
+  return (
+    <span className="shortcut-recorder">
+      <button
+        type="button"
+        onClick={isRecording ? () => invoke("cancel_shortcut_recording") : startRecording}
+        className={`shortcut-record ${isRecording ? "recording" : ""}`}
+        data-tauri-drag-region="false"
+      >
+        {isRecording ? "Press keys… (Esc to cancel)" : "Record"}
+      </button>
+      {hint && <span className="shortcut-record-note">{hint}</span>}
+      {conflict && <span className="shortcut-record-note">⚠️ Already used by {conflict}</span>}
+    </span>
+  );
+}
+
+export default ShortcutRecorder;
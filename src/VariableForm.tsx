@@ -0,0 +1,120 @@
+import { useState, useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import "./App.css";
+
+interface InputField {
+  name: string;
+  default?: string;
+  history: string[];
+}
+
+interface InputRequest {
+  id: string;
+  fields: InputField[];
+}
+
+// Start from the template's default, or else the value used last time.
+function initialValues(request: InputRequest): Record<string, string> {
+  const values: Record<string, string> = {};
+  for (const field of request.fields) {
+    values[field.name] = field.default ?? field.history[0] ?? "";
+  }
+  return values;
+}
+
+function VariableForm() {
+  const [request, setRequest] = useState<InputRequest | null>(null);
+  const [values, setValues] = useState<Record<string, string>>({});
+
+  const load = (next: InputRequest | null) => {
+    setRequest(next);
+    setValues(next ? initialValues(next) : {});
+  };
+
+  useEffect(() => {
+    invoke<InputRequest | null>("get_pending_inputs").then(load).catch(console.error);
+
+    // The window is reused, so later requests arrive as events.
+    const unlistenPromise = getCurrentWindow().listen<InputRequest>("input-form-requested", (event) => {
+      load(event.payload);
+    });
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, []);
+
+  const submit = async () => {
+    if (!request) return;
+    try {
+      await invoke("submit_inputs", { id: request.id, values });
+    } catch (e) {
+      console.error("Failed to submit inputs:", e);
+    }
+    load(null);
+  };
+
+  const cancel = async () => {
+    if (request) {
+      try {
+        await invoke("cancel_inputs", { id: request.id });
+      } catch (e) {
+        console.error("Failed to cancel inputs:", e);
+      }
+    }
+    load(null);
+    getCurrentWindow().hide();
+  };
+
+  useEffect(() => {
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (e.key === "Escape") {
+        e.preventDefault();
+        cancel();
+      }
+    };
+    window.addEventListener("keydown", handleKeyDown);
+    return () => window.removeEventListener("keydown", handleKeyDown);
+  });
+
+  if (!request) {
+    return <div className="prompt-editor">Loading...</div>;
+  }
+
+  return (
+    <form
+      className="prompt-editor"
+      onSubmit={(e) => {
+        e.preventDefault();
+        submit();
+      }}
+    >
+      <h2>Fill in prompt</h2>
+
+      {request.fields.map((field, i) => (
+        <label key={field.name}>
+          {field.name}:
+          <input
+            value={values[field.name] ?? ""}
+            onChange={(e) => setValues({ ...values, [field.name]: e.target.value })}
+            list={`history-${i}`}
+            autoFocus={i === 0}
+            className="editor-input"
+          />
+          <datalist id={`history-${i}`}>
+            {field.history.map((value) => (
+              <option key={value} value={value} />
+            ))}
+          </datalist>
+        </label>
+      ))}
+
+      <div className="editor-buttons">
+        <button type="submit" className="save-btn">Insert</button>
+        <button type="button" onClick={cancel} className="cancel-btn">Cancel</button>
+      </div>
+    </form>
+  );
+}
+
+export default VariableForm;
@@ -3,6 +3,7 @@ import ReactDOM from "react-dom/client";
 import App from "./App";
 import SettingsPage from "./SettingsPage";
 import PromptEditor from "./PromptEditor";
+import VariableForm from "./VariableForm";
 
 const params = new URLSearchParams(window.location.search);
 let Component: React.ComponentType = App;
@@ -10,6 +11,8 @@ if (params.has("settings")) {
   Component = SettingsPage;
 } else if (params.has("edit") || params.has("add")) {
   Component = PromptEditor;
+} else if (params.has("variables")) {
+  Component = VariableForm;
 }
 
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(